@@ -1,8 +1,9 @@
+use std::path::PathBuf;
 use clap::Parser;
 use anyhow::Result;
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 use tracing::Level;
-use commands::{update, new};
+use commands::{update, new, info, list, search, source, template, new::conflict::ConflictPolicy, output::OutputFormat};
 
 #[derive(Parser)]
 struct Cli {
@@ -14,32 +15,195 @@ struct Cli {
     command: Option<Commands>,
 }
 
+#[derive(Parser)]
+enum SourceCommands {
+    /// 添加模板来源
+    Add {
+        /// 来源名称
+        name: String,
+        /// Git 仓库地址，可以写成 URL#分支、标签或提交
+        url: String,
+        /// 固定的分支、标签或提交，优先于 URL 中的 #ref
+        #[arg(long = "ref", value_name = "REF")]
+        reference: Option<String>,
+    },
+    /// 删除模板来源
+    Remove {
+        /// 来源名称
+        name: String,
+    },
+    /// 列出模板来源
+    List,
+}
+
+#[derive(Parser)]
+enum TemplateCommands {
+    /// 校验模板：scaffold.json 格式、Handlebars 语法、变量引用和条件文件
+    Validate {
+        /// 模板目录
+        dir: PathBuf,
+        /// 输出格式
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// 运行模板的快照测试：按 tests/*.json 中的答案生成项目并与快照比较
+    Test {
+        /// 模板目录
+        dir: PathBuf,
+        /// 用生成结果更新快照
+        #[arg(long)]
+        bless: bool,
+    },
+    /// 由已有项目生成模板：复制到新目录，替换项目名称等字符串并生成 scaffold.json
+    Init {
+        /// 项目目录，不会被修改
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+        /// 模板输出目录，缺省为项目旁边的 <目录名>-template
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// 不询问，全部采用检测到的默认值
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
 #[derive(Parser)]
 enum Commands {
-    UPDATE,
+    /// 更新本地代码仓库
+    Update {
+        /// 恢复上一次更新前的模板
+        #[arg(long)]
+        rollback: bool,
+    },
+    /// 列出已安装的模板
+    List {
+        /// 只列出该语言的模板
+        #[arg(long)]
+        language: Option<String>,
+        /// 只列出包含该标签的模板，可重复
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        /// 只列出该来源的模板
+        #[arg(long)]
+        source: Option<String>,
+        /// 输出格式
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// 按关键字搜索模板，匹配名称、描述、标签和语言
+    Search {
+        /// 搜索关键字，多个关键字用空格分隔
+        query: String,
+        /// 输出格式
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// 显示模板详情
+    Info {
+        /// 模板名称（可以写成 来源/模板）或本地模板目录
+        template: String,
+        /// 输出格式
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// 管理模板来源
+    Source {
+        #[command(subcommand)]
+        command: SourceCommands,
+    },
+    /// 模板开发工具
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommands,
+    },
+    /// 打印 scaffold.json 的 JSON Schema
+    Schema,
     /// 创建新项目
     New {
-        /// 模板名称
+        /// 模板名称，可以写成 来源/模板
         #[arg(short, long)]
         template: Option<String>,
         /// 项目名称
         #[arg(short, long)]
         name: Option<String>,
+        /// 答案文件（JSON 或 YAML），用于非交互模式
+        #[arg(long, value_name = "FILE")]
+        answers: Option<PathBuf>,
+        /// 设置变量的值，可重复
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        set: Vec<String>,
+        /// 接受所有默认值，不再询问
+        #[arg(short, long)]
+        yes: bool,
+        /// 只打印将要生成的文件树，不写入文件
+        #[arg(long)]
+        dry_run: bool,
+        /// dry run 的输出格式
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+        /// 生成到已有目录中
+        #[arg(long, value_name = "DIR")]
+        into: Option<PathBuf>,
+        /// 已有文件内容不同时的处理方式
+        #[arg(long, value_enum, default_value_t = ConflictPolicy::Prompt)]
+        conflict: ConflictPolicy,
+        /// 不执行模板中声明的钩子
+        #[arg(long)]
+        no_hooks: bool,
+        /// 信任模板并执行其钩子，不再确认
+        #[arg(long)]
+        trust: bool,
     },
 }
 
-fn main() -> Result<()> {
+fn main() {
     let app = Cli::parse();
 
     // 初始化 tracing
     init_tracing(app.log_level);
 
-    match app.command {
-        Some(Commands::UPDATE) => {
-            update::update()?;
+    if let Err(e) = run(app.command) {
+        eprintln!("Error: {:?}", e);
+        std::process::exit(exit_code(&e));
+    }
+}
+
+/// 执行子命令
+fn run(command: Option<Commands>) -> Result<()> {
+    match command {
+        Some(Commands::Update { rollback }) => {
+            if rollback {
+                update::rollback()?;
+            } else {
+                update::update()?;
+            }
+        }
+        Some(Commands::New { template, name, answers, set, yes, dry_run, format, into, conflict, no_hooks, trust }) => {
+            let options = new::NewOptions { answers, set, yes, dry_run, format, into, conflict, no_hooks, trust, ..Default::default() };
+            new::new(template, name, options)?;
         }
-        Some(Commands::New { template, name }) => {
-            new::new(template, name)?;
+        Some(Commands::List { language, tags, source, format }) => {
+            list::list(list::ListOptions { language, tags, source, format })?;
+        }
+        Some(Commands::Search { query, format }) => {
+            search::search(query, format)?;
+        }
+        Some(Commands::Info { template, format }) => {
+            info::info(template, format)?;
+        }
+        Some(Commands::Source { command }) => match command {
+            SourceCommands::Add { name, url, reference } => source::add(name, url, reference)?,
+            SourceCommands::Remove { name } => source::remove(name)?,
+            SourceCommands::List => source::list()?,
+        },
+        Some(Commands::Template { command }) => match command {
+            TemplateCommands::Validate { dir, format } => template::validate(dir, format)?,
+            TemplateCommands::Test { dir, bless } => template::test(dir, bless)?,
+            TemplateCommands::Init { dir, output, yes } => template::init(dir, output, yes)?,
+        },
+        Some(Commands::Schema) => {
+            template::schema()?;
         }
         None => {
             println!("请使用 --help 查看使用说明");
@@ -49,6 +213,15 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// 按错误类型确定进程退出码
+fn exit_code(error: &anyhow::Error) -> i32 {
+    if error.downcast_ref::<update::InvalidTemplates>().is_some() {
+        update::InvalidTemplates::EXIT_CODE
+    } else {
+        1
+    }
+}
+
 /// 初始化 tracing 日志系统
 fn init_tracing(log_level: Option<String>) {
     // 确定默认日志级别：debug 构建默认 info，release 构建默认 error
@@ -83,6 +256,7 @@ fn init_tracing(log_level: Option<String>) {
         .with(filter)
         .with(
             fmt::layer()
+                .with_writer(std::io::stderr)
                 .with_target(true)
                 .with_line_number(true)
         )
@@ -0,0 +1,104 @@
+//! 条件文件规则
+//!
+//! 模板在 scaffold.json 的 `conditions` 中用 glob 匹配路径，`when` 为假时跳过匹配到的文件和目录
+
+use anyhow::{Result, Context};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use model::ConditionRule;
+use std::path::Path;
+
+/// 求值后的条件规则集合
+pub struct PathFilter {
+    rules: Vec<(ConditionRule, bool)>,
+    globs: GlobSet,
+}
+
+impl PathFilter {
+    /// 编译 glob 并对每条规则的表达式求值
+    pub fn new<F>(rules: &[ConditionRule], mut eval: F) -> Result<Self>
+    where
+        F: FnMut(&str) -> Result<bool>,
+    {
+        let mut builder = GlobSetBuilder::new();
+        let mut evaluated = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let glob = Glob::new(&rule.path)
+                .with_context(|| format!("条件规则的路径 {} 不是合法的 glob", rule.path))?;
+            builder.add(glob);
+            let enabled = eval(&rule.when)
+                .with_context(|| format!("条件规则 {} 的表达式 {} 求值失败", rule.path, rule.when))?;
+            evaluated.push((rule.clone(), enabled));
+        }
+        let globs = builder.build().with_context(|| "编译条件规则失败")?;
+        Ok(Self { rules: evaluated, globs })
+    }
+
+    /// 返回导致该路径被跳过的规则，路径应包含时返回 None
+    pub fn excluded_by(&self, relative: &Path, is_dir: bool) -> Option<&ConditionRule> {
+        self.matches(relative, is_dir)
+            .into_iter()
+            .map(|index| &self.rules[index])
+            .find(|(_, enabled)| !enabled)
+            .map(|(rule, _)| rule)
+    }
+
+    /// 匹配该路径的规则，不论表达式是否成立
+    pub fn rules_for(&self, relative: &Path, is_dir: bool) -> Vec<&ConditionRule> {
+        self.matches(relative, is_dir)
+            .into_iter()
+            .map(|index| &self.rules[index].0)
+            .collect()
+    }
+
+    /// 匹配该路径的规则下标
+    ///
+    /// 目录同时以 `dir/` 的形式匹配，使 `docker/**` 可以跳过整个 docker 目录
+    fn matches(&self, relative: &Path, is_dir: bool) -> Vec<usize> {
+        let mut matches = self.globs.matches(relative);
+        if is_dir {
+            matches.extend(self.globs.matches(format!("{}/", relative.display())));
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(path: &str, when: &str) -> ConditionRule {
+        ConditionRule { path: path.to_string(), when: when.to_string() }
+    }
+
+    /// 表达式为变量名，取值为真的变量列在 `enabled` 中
+    fn filter(rules: &[ConditionRule], enabled: &[&str]) -> PathFilter {
+        PathFilter::new(rules, |expr| Ok(enabled.contains(&expr))).unwrap()
+    }
+
+    #[test]
+    fn directory_glob_excludes_directory_and_contents() {
+        let filter = filter(&[rule("docker/**", "use_docker")], &[]);
+        assert_eq!(filter.excluded_by(Path::new("docker"), true).map(|r| r.when.as_str()), Some("use_docker"));
+        assert!(filter.excluded_by(Path::new("docker/Dockerfile"), false).is_some());
+        assert!(filter.excluded_by(Path::new("docker"), false).is_none());
+        assert!(filter.excluded_by(Path::new("src/docker.rs"), false).is_none());
+    }
+
+    #[test]
+    fn every_matching_rule_must_hold() {
+        let rules = [rule("ci/**", "use_ci"), rule("ci/docker.yml", "use_docker")];
+        let only_ci = filter(&rules, &["use_ci"]);
+        assert!(only_ci.excluded_by(Path::new("ci/test.yml"), false).is_none());
+        assert_eq!(only_ci.excluded_by(Path::new("ci/docker.yml"), false).map(|r| r.when.as_str()), Some("use_docker"));
+        assert_eq!(only_ci.rules_for(Path::new("ci/docker.yml"), false).len(), 2);
+
+        let both = filter(&rules, &["use_ci", "use_docker"]);
+        assert!(both.excluded_by(Path::new("ci/docker.yml"), false).is_none());
+    }
+
+    #[test]
+    fn invalid_glob_and_failed_evaluation_are_errors() {
+        assert!(PathFilter::new(&[rule("src/[", "x")], |_| Ok(true)).is_err());
+        assert!(PathFilter::new(&[rule("src/**", "x")], |_| anyhow::bail!("boom")).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-/// 全局配置常量
+//! 全局配置常量
 
 /// scaffold 根目录名称
 pub const SCAFFOLD_DIR_NAME: &str = "scaffold";
@@ -9,14 +9,29 @@ pub const CODES_DIR_NAME: &str = "codes";
 /// 临时目录后缀
 pub const TEMP_DIR_SUFFIX: &str = "-temp";
 
+/// 上一次更新前的模板快照目录后缀
+pub const PREV_DIR_SUFFIX: &str = "-prev";
+
+/// 新项目暂存目录后缀
+pub const STAGING_DIR_SUFFIX: &str = ".scaffold-staging";
+
 /// 模板配置文件名
 pub const SCAFFOLD_CONFIG_FILE: &str = "scaffold.json";
 
+/// 模板测试用例目录，其中的 `<用例>.json` 和同名快照目录不会生成到项目中
+pub const TEMPLATE_TESTS_DIR: &str = "tests";
+
+/// 来源仓库中排除模板搜索目录的文件名
+pub const SCAFFOLD_IGNORE_FILE: &str = ".scaffoldignore";
+
+/// 在来源仓库中搜索模板目录的最大深度
+pub const TEMPLATE_MAX_DEPTH: usize = 4;
+
 /// 用户主目录下的 scaffold 配置目录
 pub const HOME_SCAFFOLD_DIR: &str = ".scaffold";
 
 /// 配置文件路径（相对于用户主目录）
 pub const HOME_SCAFFOLD_CONFIG: &str = ".scaffold/config.json";
 
-/// GitHub 模板仓库 URL
-pub const GITHUB_REPO_URL: &str = "https://github.com/casemcx/code-template";
+/// 模板钩子信任记录路径（相对于用户主目录）
+pub const HOME_SCAFFOLD_TRUST: &str = ".scaffold/trust.json";
@@ -0,0 +1,145 @@
+//! 模板发现
+//!
+//! 在来源仓库中递归查找包含 scaffold.json 的目录。模板目录的上级路径作为分类，
+//! 如 `frontend/react-app/scaffold.json` 的分类为 `frontend`。
+//! 来源根目录下的 `.scaffoldignore` 每行一个 glob，匹配的目录不会被搜索
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{Result, Context};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use walkdir::WalkDir;
+use crate::config;
+
+/// 发现的模板目录
+#[derive(Debug, Clone)]
+pub struct TemplateDir {
+    /// 模板目录
+    pub path: PathBuf,
+    /// 相对来源根目录的上级路径，位于根目录下一级时为 None
+    pub category: Option<String>,
+}
+
+/// 在 `root` 下查找模板目录，最多向下 `max_depth` 层
+///
+/// 找到 scaffold.json 后不再进入该目录，隐藏目录（如 `.git`）总是被跳过
+pub fn discover(root: &Path, max_depth: usize) -> Result<Vec<TemplateDir>> {
+    let ignore = load_ignore(root)?;
+    let mut templates = Vec::new();
+
+    let mut walker = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .sort_by_file_name()
+        .into_iter();
+    while let Some(entry) = walker.next() {
+        let entry = entry.with_context(|| format!("遍历目录 {} 失败", root.display()))?;
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if hidden || ignore.is_match(relative) {
+            walker.skip_current_dir();
+            continue;
+        }
+
+        if entry.path().join(config::SCAFFOLD_CONFIG_FILE).is_file() {
+            let category = relative
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .map(|p| {
+                    p.components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/")
+                });
+            templates.push(TemplateDir { path: entry.path().to_path_buf(), category });
+            walker.skip_current_dir();
+        }
+    }
+
+    Ok(templates)
+}
+
+/// 读取来源根目录的 `.scaffoldignore`
+///
+/// 空行和 `#` 开头的行被忽略；以 `/` 开头或包含 `/` 的规则相对根目录匹配，
+/// 其余规则匹配任意层级的同名目录
+fn load_ignore(root: &Path) -> Result<GlobSet> {
+    let path = root.join(config::SCAFFOLD_IGNORE_FILE);
+    let mut builder = GlobSetBuilder::new();
+    if path.is_file() {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("读取文件 {} 失败", path.display()))?;
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let pattern = line.trim_end_matches('/');
+            let pattern = match pattern.strip_prefix('/') {
+                Some(anchored) => anchored.to_string(),
+                None if pattern.contains('/') => pattern.to_string(),
+                None => format!("**/{}", pattern),
+            };
+            let glob = GlobBuilder::new(&pattern)
+                .literal_separator(true)
+                .build()
+                .with_context(|| format!("{} 中的规则 {} 无效", path.display(), line))?;
+            builder.add(glob);
+        }
+    }
+    builder.build().with_context(|| format!("解析 {} 失败", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 创建来源目录，`dirs` 中的每个目录都放一个 scaffold.json
+    fn source(dirs: &[&str], ignore: Option<&str>) -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        for dir in dirs {
+            let dir = root.path().join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(config::SCAFFOLD_CONFIG_FILE), "{}").unwrap();
+        }
+        if let Some(ignore) = ignore {
+            fs::write(root.path().join(config::SCAFFOLD_IGNORE_FILE), ignore).unwrap();
+        }
+        root
+    }
+
+    fn found(root: &Path, max_depth: usize) -> Vec<(String, Option<String>)> {
+        discover(root, max_depth)
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"), t.category))
+            .collect()
+    }
+
+    #[test]
+    fn categories_and_depth_limit() {
+        let root = source(&["rust-cli", "web/react/app", "web/vue", "a/b/c/deep", "rust-cli/nested"], None);
+        assert_eq!(
+            found(root.path(), 3),
+            [
+                ("rust-cli".to_string(), None),
+                ("web/react/app".to_string(), Some("web/react".to_string())),
+                ("web/vue".to_string(), Some("web".to_string())),
+            ]
+        );
+        assert_eq!(found(root.path(), 4).len(), 4);
+    }
+
+    #[test]
+    fn hidden_and_ignored_directories_are_skipped() {
+        let ignore = "# 注释\n\nexamples/\n/web/legacy\n";
+        let root = source(&[".hidden/t", "examples", "web/examples", "web/legacy", "web/new", "legacy"], Some(ignore));
+        assert_eq!(
+            found(root.path(), 3),
+            [("legacy".to_string(), None), ("web/new".to_string(), Some("web".to_string()))]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! 模板钩子
+//!
+//! 模板在 scaffold.json 的 `hooks` 中声明生成前后执行的命令，命令通过 shell 在目标目录中执行
+
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+use anyhow::{Result, Context};
+use colored::Colorize;
+use serde_json::Value;
+
+/// 钩子环境变量前缀
+pub const ENV_PREFIX: &str = "SCAFFOLD_";
+
+/// 由渲染上下文生成钩子的环境变量
+///
+/// 顶层的每个值导出为 `SCAFFOLD_<大写变量名>`，数组以逗号连接，`template` 只导出名称
+pub fn hook_env(context: &Value) -> Vec<(String, String)> {
+    let Value::Object(map) = context else {
+        return Vec::new();
+    };
+
+    map.iter()
+        .filter_map(|(key, value)| {
+            let value = match (key.as_str(), value) {
+                ("template", Value::Object(template)) => template.get("name")?.as_str()?.to_string(),
+                (_, Value::String(s)) => s.clone(),
+                (_, Value::Array(items)) => items
+                    .iter()
+                    .map(|i| i.as_str().map_or_else(|| i.to_string(), String::from))
+                    .collect::<Vec<_>>()
+                    .join(","),
+                (_, Value::Null) => String::new(),
+                (_, other) => other.to_string(),
+            };
+            Some((env_name(key), value))
+        })
+        .collect()
+}
+
+/// 变量名转换为环境变量名
+fn env_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    format!("{}{}", ENV_PREFIX, name)
+}
+
+/// 依次执行一组钩子，遇到失败立即停止
+///
+/// 每条命令的结果都会打印出来，全部成功返回 Ok
+pub fn run_hooks(stage: &str, commands: &[String], dir: &Path, env: &[(String, String)]) -> Result<()> {
+    let total = commands.len();
+    for (index, command) in commands.iter().enumerate() {
+        println!("{} {}", format!("▶ [{} {}/{}]", stage, index + 1, total).cyan(), command.bold());
+
+        let started = Instant::now();
+        let status = shell(command)
+            .current_dir(dir)
+            .envs(env.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .status()
+            .with_context(|| format!("执行钩子 {} 失败", command))?;
+        let elapsed = format!("({:.1}s)", started.elapsed().as_secs_f64()).dimmed();
+
+        if status.success() {
+            println!("{} {} {}", "✓".green(), command, elapsed);
+        } else {
+            let code = status.code().map_or_else(|| "被信号终止".to_string(), |c| format!("退出码 {}", c));
+            println!("{} {} {} {}", "✗".red(), command, code.red(), elapsed);
+            let skipped = total - index - 1;
+            if skipped > 0 {
+                println!("{}", format!("  跳过剩余 {} 个 {} 钩子", skipped, stage).yellow());
+            }
+            anyhow::bail!("{} 钩子执行失败: {}（{}）", stage, command, code);
+        }
+    }
+    Ok(())
+}
+
+/// 通过系统 shell 执行命令，以支持通配符和管道
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
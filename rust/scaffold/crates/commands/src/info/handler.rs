@@ -0,0 +1,217 @@
+use std::env;
+use std::path::Path;
+use anyhow::{Result, Context};
+use colored::{Color, Colorize};
+use serde::Serialize;
+use walkdir::WalkDir;
+use model::{TemplateEntry, TemplateIndex};
+use utils::size::format_size;
+use crate::config;
+use crate::new::handler::{find_config_by_name, local_template_path, read_local_config};
+use crate::output::{OutputFormat, Table};
+
+/// 文件树预览最多显示的行数
+const TREE_PREVIEW_LINES: usize = 40;
+
+pub struct InfoHandler {
+    home_dir: String,
+    current_dir: String,
+}
+
+/// 模板详情，`--format json` 的输出
+#[derive(Debug, Serialize)]
+struct TemplateInfo {
+    #[serde(flatten)]
+    template: TemplateEntry,
+    /// 来源固定的分支、标签或提交
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    reference: Option<String>,
+    files: Vec<FileInfo>,
+    total_size: u64,
+}
+
+/// 模板目录中的一个条目
+#[derive(Debug, Serialize)]
+struct FileInfo {
+    /// 相对模板目录的路径
+    path: String,
+    /// 是否为目录
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    dir: bool,
+    size: u64,
+}
+
+impl InfoHandler {
+    pub fn new() -> Self {
+        let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        let current_dir = env::current_dir()
+            .unwrap_or_else(|_| Path::new(".").to_path_buf())
+            .to_string_lossy()
+            .to_string();
+        Self { home_dir, current_dir }
+    }
+
+    pub fn execute(&self, template: &str, format: OutputFormat) -> Result<()> {
+        // 与 new 相同：本地目录直接读取，否则按名称在索引中查找
+        let (config, index) = match local_template_path(template, &self.home_dir) {
+            Some(dir) => (read_local_config(&Path::new(&self.current_dir).join(dir))?, None),
+            None => {
+                let config_file = format!("{}/{}", self.home_dir, config::HOME_SCAFFOLD_CONFIG);
+                let index = TemplateIndex::load(Path::new(&config_file))?;
+                if index.templates.is_empty() {
+                    anyhow::bail!("未找到模板索引，请先运行 'scaffold update' 下载模板");
+                }
+                (find_config_by_name(&index.templates, template)?, Some(index))
+            }
+        };
+
+        let reference = index.zip(config.source.as_ref()).and_then(|(index, name)| {
+            index
+                .sources()
+                .into_iter()
+                .find(|s| &s.name == name)
+                .and_then(|s| s.remote().1.map(String::from))
+        });
+        let files = collect_files(Path::new(&config.path))?;
+        let total_size = files.iter().map(|f| f.size).sum();
+        let info = TemplateInfo { template: config, reference, files, total_size };
+
+        match format {
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(&info).with_context(|| "序列化模板详情失败")?;
+                println!("{}", json);
+            }
+            OutputFormat::Text => print_info(&info),
+        }
+        Ok(())
+    }
+}
+
+/// 按路径顺序收集模板目录中的文件和目录，跳过 `.git`
+fn collect_files(dir: &Path) -> Result<Vec<FileInfo>> {
+    let mut files = Vec::new();
+    let walker = WalkDir::new(dir)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git");
+    for entry in walker {
+        let entry = entry.with_context(|| format!("遍历模板目录 {} 失败", dir.display()))?;
+        let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+        let is_dir = entry.file_type().is_dir();
+        let size = if is_dir {
+            0
+        } else {
+            entry.metadata().with_context(|| format!("读取 {} 失败", entry.path().display()))?.len()
+        };
+        files.push(FileInfo {
+            path: relative.to_string_lossy().replace('\\', "/"),
+            dir: is_dir,
+            size,
+        });
+    }
+    Ok(files)
+}
+
+fn print_info(info: &TemplateInfo) {
+    let template = &info.template;
+    let manifest = &template.manifest;
+    println!("{} {}", manifest.name.cyan().bold(), format!("({})", template.qualified_name()).dimmed());
+    if let Some(description) = &manifest.description {
+        println!("  {}", description);
+    }
+    println!();
+
+    let source = template.source.as_ref().map(|name| {
+        let url = template.source_url.as_deref().unwrap_or_default();
+        match &info.reference {
+            Some(reference) => format!("{} ({}#{})", name, url, reference),
+            None => format!("{} ({})", name, url),
+        }
+    });
+    let fields = [
+        ("版本", manifest.version.clone()),
+        ("作者", manifest.author.clone()),
+        ("语言", manifest.language.clone()),
+        ("标签", manifest.tags.as_ref().map(|t| t.join(", "))),
+        ("分类", template.category.clone()),
+        ("来源", source.or_else(|| Some("本地目录".to_string()))),
+        ("提交", template.commit.clone()),
+        ("路径", Some(template.path.clone())),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value {
+            println!("  {}  {}", label.dimmed(), value);
+        }
+    }
+
+    if !manifest.variables.is_empty() {
+        println!();
+        println!("{}", "变量".bold());
+        let mut table = Table::new(&["名称", "类型", "默认值", "可选项", "说明"])
+            .color(0, Color::Cyan)
+            .color(1, Color::Green);
+        for variable in &manifest.variables {
+            table.row(vec![
+                variable.name.clone(),
+                variable.kind.type_name().to_string(),
+                variable.kind.declared_default().map(|d| display_value(&d)).unwrap_or_default(),
+                variable.kind.options().map(|o| o.join(", ")).unwrap_or_default(),
+                variable.help.clone().or_else(|| variable.prompt.clone()).unwrap_or_default(),
+            ]);
+        }
+        table.print();
+    }
+
+    if !manifest.conditions.is_empty() {
+        println!();
+        println!("{}", "条件文件".bold());
+        let mut table = Table::new(&["路径", "条件"]).color(0, Color::Yellow);
+        for rule in &manifest.conditions {
+            table.row(vec![rule.path.clone(), rule.when.clone()]);
+        }
+        table.print();
+    }
+
+    if !manifest.hooks.is_empty() {
+        println!();
+        println!("{}", "钩子".bold());
+        for (stage, commands) in [("pre_generate", &manifest.hooks.pre_generate), ("post_generate", &manifest.hooks.post_generate)] {
+            for command in commands {
+                println!("  {} {}", format!("[{}]", stage).cyan(), command);
+            }
+        }
+    }
+
+    let file_count = info.files.iter().filter(|f| !f.dir).count();
+    println!();
+    println!("{} {}", "文件".bold(), format!("({} 个文件，{})", file_count, format_size(info.total_size)).dimmed());
+    for file in info.files.iter().take(TREE_PREVIEW_LINES) {
+        let depth = file.path.matches('/').count();
+        let name = file.path.rsplit('/').next().unwrap_or(&file.path);
+        let indent = "  ".repeat(depth + 1);
+        if file.dir {
+            println!("{}{}", indent, format!("{}/", name).blue().bold());
+        } else {
+            println!("{}{} {}", indent, name, format_size(file.size).dimmed());
+        }
+    }
+    if info.files.len() > TREE_PREVIEW_LINES {
+        println!("  {}", format!("… 还有 {} 项", info.files.len() - TREE_PREVIEW_LINES).dimmed());
+    }
+}
+
+/// 默认值的展示文本，字符串不加引号
+fn display_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items.iter().map(display_value).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+impl Default for InfoHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
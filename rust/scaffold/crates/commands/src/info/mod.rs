@@ -0,0 +1,11 @@
+pub mod handler;
+
+use anyhow::Result;
+use handler::InfoHandler;
+use crate::output::OutputFormat;
+
+/// 显示模板详情
+pub fn info(template: String, format: OutputFormat) -> Result<()> {
+    let handler = InfoHandler::new();
+    handler.execute(&template, format)
+}
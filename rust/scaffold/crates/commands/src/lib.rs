@@ -1,3 +1,13 @@
+pub mod conditions;
 pub mod config;
+pub mod discovery;
+pub mod hooks;
+pub mod info;
+pub mod list;
 pub mod update;
-pub mod new;
\ No newline at end of file
+pub mod new;
+pub mod output;
+pub mod search;
+pub mod source;
+pub mod template;
+pub mod trust;
\ No newline at end of file
@@ -0,0 +1,120 @@
+use std::path::Path;
+use anyhow::{Result, Context};
+use colored::{Color, Colorize};
+use model::index::short_commit;
+use model::{TemplateEntry, TemplateIndex};
+use crate::config;
+use crate::output::{OutputFormat, Table};
+use super::ListOptions;
+
+pub struct ListHandler {
+    home_dir: String,
+}
+
+impl ListHandler {
+    /// 读取 `home_dir` 下的模板索引
+    pub fn new(home_dir: &Path) -> Self {
+        Self { home_dir: home_dir.to_string_lossy().to_string() }
+    }
+
+    /// 按过滤条件返回已安装的模板
+    pub fn templates(&self, options: &ListOptions) -> Result<Vec<TemplateEntry>> {
+        let index = self.load_index()?;
+        Ok(filter_templates(&index.templates, options).into_iter().cloned().collect())
+    }
+
+    pub fn execute(&self, options: &ListOptions) -> Result<()> {
+        let index = self.load_index()?;
+        if index.templates.is_empty() {
+            // 提示写到 stderr，JSON 输出仍然是合法的空数组
+            if options.format == OutputFormat::Json {
+                println!("[]");
+            }
+            eprintln!("未找到模板索引，请先运行 {} 下载模板", "scaffold update".cyan());
+            return Ok(());
+        }
+
+        let templates = filter_templates(&index.templates, options);
+        if options.format == OutputFormat::Json {
+            let json = serde_json::to_string_pretty(&templates).with_context(|| "序列化模板列表失败")?;
+            println!("{}", json);
+            return Ok(());
+        }
+
+        if templates.is_empty() {
+            println!("没有匹配的模板（共 {} 个模板）", index.templates.len());
+            return Ok(());
+        }
+
+        template_table(&templates).print();
+
+        if templates.len() < index.templates.len() {
+            println!("{}", format!("显示 {} / {} 个模板", templates.len(), index.templates.len()).dimmed());
+        }
+        Ok(())
+    }
+
+    fn load_index(&self) -> Result<TemplateIndex> {
+        let config_file = format!("{}/{}", self.home_dir, config::HOME_SCAFFOLD_CONFIG);
+        TemplateIndex::load(Path::new(&config_file))
+    }
+}
+
+/// 符合过滤条件的模板，按来源、分类和名称排序
+fn filter_templates<'a>(templates: &'a [TemplateEntry], options: &ListOptions) -> Vec<&'a TemplateEntry> {
+    let mut templates: Vec<&TemplateEntry> = templates.iter().filter(|t| matches_filters(t, options)).collect();
+    templates.sort_by(|a, b| (&a.source, &a.category, a.name()).cmp(&(&b.source, &b.category, b.name())));
+    templates
+}
+
+/// 模板列表表格，`list` 和 `search` 共用
+pub(crate) fn template_table(templates: &[&TemplateEntry]) -> Table {
+    let mut table = Table::new(&["模板", "来源", "分类", "语言", "标签", "提交", "描述"])
+        .color(0, Color::Cyan)
+        .color(3, Color::Green)
+        .color(4, Color::Yellow)
+        .color(5, Color::BrightBlack);
+    for template in templates {
+        let manifest = &template.manifest;
+        table.row(vec![
+            manifest.name.clone(),
+            template.source.clone().unwrap_or_default(),
+            template.category.clone().unwrap_or_default(),
+            manifest.language.clone().unwrap_or_default(),
+            manifest.tags.as_ref().map(|t| t.join(",")).unwrap_or_default(),
+            template.commit.as_deref().map(short_commit).unwrap_or_default().to_string(),
+            manifest.description.clone().unwrap_or_default(),
+        ]);
+    }
+    table
+}
+
+/// 模板是否满足所有过滤条件
+fn matches_filters(template: &TemplateEntry, options: &ListOptions) -> bool {
+    let language = options.language.as_ref().is_none_or(|language| {
+        template
+            .manifest
+            .language
+            .as_ref()
+            .is_some_and(|l| l.eq_ignore_ascii_case(language))
+    });
+    let tags = options.tags.iter().all(|tag| {
+        template
+            .manifest
+            .tags
+            .as_ref()
+            .is_some_and(|tags| tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    });
+    let source = options
+        .source
+        .as_ref()
+        .is_none_or(|source| template.source.as_ref() == Some(source));
+    language && tags && source
+}
+
+impl Default for ListHandler {
+    fn default() -> Self {
+        let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        Self::new(Path::new(&home_dir))
+    }
+}
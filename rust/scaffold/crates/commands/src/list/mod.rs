@@ -0,0 +1,23 @@
+pub mod handler;
+
+use anyhow::Result;
+use handler::ListHandler;
+use crate::output::OutputFormat;
+
+/// `scaffold list` 的过滤条件和输出格式
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    /// 只列出该语言的模板，不区分大小写
+    pub language: Option<String>,
+    /// 只列出包含全部这些标签的模板
+    pub tags: Vec<String>,
+    /// 只列出该来源的模板
+    pub source: Option<String>,
+    pub format: OutputFormat,
+}
+
+/// 列出已安装的模板
+pub fn list(options: ListOptions) -> Result<()> {
+    let handler = ListHandler::default();
+    handler.execute(&options)
+}
@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
+use anyhow::{Result, Context};
+use serde_json::Value;
+
+/// 预先提供的答案
+///
+/// 来源按优先级从低到高为：答案文件（JSON / YAML）、`--set key=value`
+pub struct Answers {
+    values: BTreeMap<String, String>,
+    accept_defaults: bool,
+    interactive: bool,
+}
+
+impl Answers {
+    /// 读取答案文件并合并 `--set` 参数
+    pub fn load(file: Option<&Path>, sets: &[String], accept_defaults: bool) -> Result<Self> {
+        let mut values = BTreeMap::new();
+
+        if let Some(file) = file {
+            for (key, value) in read_answers_file(file)? {
+                let value = answer_to_input(&value)
+                    .with_context(|| format!("答案文件 {} 中 {} 的值无效", file.display(), key))?;
+                values.insert(key, value);
+            }
+        }
+
+        for set in sets {
+            let (key, value) = set
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("--set 参数 {} 格式错误，应为 key=value", set))?;
+            values.insert(key.trim().to_string(), value.to_string());
+        }
+
+        Ok(Self {
+            values,
+            accept_defaults,
+            interactive: std::io::stdin().is_terminal(),
+        })
+    }
+
+    /// 获取某个键的答案
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// 所有提供了答案的键
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// 是否直接接受默认值（`--yes`）
+    pub fn accept_defaults(&self) -> bool {
+        self.accept_defaults
+    }
+
+    /// 标准输入是否为终端，不是终端时不能弹出交互提示
+    pub fn interactive(&self) -> bool {
+        self.interactive
+    }
+
+    /// 不再弹出交互提示，按非终端处理
+    pub fn disable_prompts(&mut self) {
+        self.interactive = false;
+    }
+
+    /// 缺少答案又不能询问时的错误
+    pub fn missing(&self, name: &str, has_default: bool) -> anyhow::Error {
+        if has_default {
+            anyhow::anyhow!(
+                "缺少 {} 的值：当前不是交互式终端，请通过 --answers 或 --set {}=<值> 提供，或使用 --yes 接受默认值",
+                name,
+                name
+            )
+        } else {
+            anyhow::anyhow!(
+                "缺少 {} 的值：当前不是交互式终端且没有默认值，请通过 --answers 或 --set {}=<值> 提供",
+                name,
+                name
+            )
+        }
+    }
+}
+
+/// 读取答案文件，根据扩展名选择 YAML 或 JSON 解析
+fn read_answers_file(file: &Path) -> Result<serde_json::Map<String, Value>> {
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("读取答案文件 {} 失败", file.display()))?;
+
+    let is_yaml = matches!(
+        file.extension().and_then(|e| e.to_str()),
+        Some("yaml") | Some("yml")
+    );
+    let value: Value = if is_yaml {
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("解析答案文件 {} 失败", file.display()))?
+    } else {
+        serde_json::from_str(&content)
+            .with_context(|| format!("解析答案文件 {} 失败", file.display()))?
+    };
+
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => anyhow::bail!("答案文件 {} 的顶层必须是对象", file.display()),
+    }
+}
+
+/// 将答案文件中的值转换为与交互输入相同的文本形式，统一走变量校验
+fn answer_to_input(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(s) => Ok(s.clone()),
+                other => anyhow::bail!("多选值只能是字符串，得到 {}", other),
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(|items| items.join(",")),
+        other => anyhow::bail!("不支持的值 {}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// 在临时目录中写入答案文件
+    fn answers_file(name: &str, content: &str) -> (TempDir, std::path::PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn yaml_and_json_files_are_parsed() {
+        let (_dir, yaml) = answers_file("answers.yml", "crate_name: demo\nuse_docker: true\nport: 8080\nfeatures: [cli, serde]\n");
+        let answers = Answers::load(Some(&yaml), &[], false).unwrap();
+        assert_eq!(answers.get("crate_name"), Some("demo"));
+        assert_eq!(answers.get("use_docker"), Some("true"));
+        assert_eq!(answers.get("port"), Some("8080"));
+        assert_eq!(answers.get("features"), Some("cli,serde"));
+
+        let (_dir, json) = answers_file("answers.json", r#"{ "crate_name": "demo", "features": [] }"#);
+        let answers = Answers::load(Some(&json), &[], false).unwrap();
+        assert_eq!(answers.keys().collect::<Vec<_>>(), ["crate_name", "features"]);
+        assert_eq!(answers.get("features"), Some(""));
+    }
+
+    #[test]
+    fn invalid_files_are_rejected() {
+        let (_dir, list) = answers_file("answers.json", "[1, 2]");
+        assert!(Answers::load(Some(&list), &[], false).is_err());
+        let (_dir, nested) = answers_file("answers.yaml", "db:\n  host: localhost\n");
+        assert!(Answers::load(Some(&nested), &[], false).is_err());
+        let (_dir, numbers) = answers_file("answers.json", r#"{ "features": [1] }"#);
+        assert!(Answers::load(Some(&numbers), &[], false).is_err());
+    }
+
+    #[test]
+    fn set_overrides_file() {
+        let (_dir, json) = answers_file("answers.json", r#"{ "crate_name": "demo", "port": 80 }"#);
+        let sets = ["crate_name=other".to_string(), " url =a=b".to_string()];
+        let answers = Answers::load(Some(&json), &sets, true).unwrap();
+        assert_eq!(answers.get("crate_name"), Some("other"));
+        assert_eq!(answers.get("port"), Some("80"));
+        assert_eq!(answers.get("url"), Some("a=b"));
+        assert!(answers.accept_defaults());
+
+        assert!(Answers::load(None, &["crate_name".to_string()], false).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use std::path::Path;
+use anyhow::Result;
+use clap::ValueEnum;
+use colored::Colorize;
+use dialoguer::{theme::ColorfulTheme, Select};
+use similar::TextDiff;
+use super::render::text_content;
+
+/// 写入 `.scaffold-new` 文件时追加的后缀
+pub const SIDECAR_SUFFIX: &str = ".scaffold-new";
+
+/// 目标文件已存在且内容不同时的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ConflictPolicy {
+    /// 保留已有文件
+    Skip,
+    /// 用模板生成的内容覆盖
+    Overwrite,
+    /// 逐个询问，并显示差异
+    #[default]
+    Prompt,
+    /// 保留已有文件，生成的内容写入同目录的 `<文件名>.scaffold-new`
+    New,
+}
+
+/// 单个冲突的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Skip,
+    Overwrite,
+    Sidecar,
+}
+
+/// 按策略决定如何处理一个冲突文件
+pub fn resolve(
+    policy: ConflictPolicy,
+    path: &Path,
+    existing: &[u8],
+    generated: &[u8],
+    interactive: bool,
+) -> Result<Resolution> {
+    match policy {
+        ConflictPolicy::Skip => Ok(Resolution::Skip),
+        ConflictPolicy::Overwrite => Ok(Resolution::Overwrite),
+        ConflictPolicy::New => Ok(Resolution::Sidecar),
+        ConflictPolicy::Prompt if !interactive => anyhow::bail!(
+            "文件 {} 已存在且内容不同：当前不是交互式终端，请使用 --conflict 指定处理方式",
+            path.display()
+        ),
+        ConflictPolicy::Prompt => {
+            print_diff(path, existing, generated);
+
+            let items = [
+                "跳过，保留已有文件".to_string(),
+                "覆盖".to_string(),
+                format!("写入 {}{}", path.display(), SIDECAR_SUFFIX),
+            ];
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("文件 {} 已存在且内容不同", path.display()))
+                .items(&items)
+                .default(0)
+                .interact()?;
+
+            Ok(match selection {
+                0 => Resolution::Skip,
+                1 => Resolution::Overwrite,
+                _ => Resolution::Sidecar,
+            })
+        }
+    }
+}
+
+/// 打印已有文件与生成内容之间的彩色 unified diff
+pub fn print_diff(path: &Path, existing: &[u8], generated: &[u8]) {
+    print_labeled_diff(path, ("已有", existing), ("模板", generated));
+}
+
+/// 打印两份内容之间的彩色 unified diff，标签显示在文件头中
+pub fn print_labeled_diff(path: &Path, (old_label, old): (&str, &[u8]), (new_label, new): (&str, &[u8])) {
+    let (Some(old), Some(new)) = (text_content(old), text_content(new)) else {
+        println!("{}", format!("二进制文件 {} 不同", path.display()).yellow());
+        return;
+    };
+
+    let name = path.display().to_string();
+    let diff = TextDiff::from_lines(old, new);
+    let unified = diff
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("{} ({})", name, old_label), &format!("{} ({})", name, new_label))
+        .to_string();
+
+    for line in unified.lines() {
+        if line.starts_with("+++") || line.starts_with("---") {
+            println!("{}", line.bold());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve_with(policy: ConflictPolicy, interactive: bool) -> Result<Resolution> {
+        resolve(policy, Path::new("src/main.rs"), b"old\n", b"new\n", interactive)
+    }
+
+    #[test]
+    fn fixed_policies_never_prompt() {
+        for interactive in [false, true] {
+            assert_eq!(resolve_with(ConflictPolicy::Skip, interactive).unwrap(), Resolution::Skip);
+            assert_eq!(resolve_with(ConflictPolicy::Overwrite, interactive).unwrap(), Resolution::Overwrite);
+            assert_eq!(resolve_with(ConflictPolicy::New, interactive).unwrap(), Resolution::Sidecar);
+        }
+    }
+
+    #[test]
+    fn prompt_without_terminal_is_an_error() {
+        let error = resolve_with(ConflictPolicy::Prompt, false).unwrap_err();
+        assert!(error.to_string().contains("src/main.rs"), "{:#}", error);
+        assert!(error.to_string().contains("--conflict"), "{:#}", error);
+    }
+}
@@ -1,25 +1,26 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use anyhow::{Result, Context};
 use colored::Colorize;
-use dialoguer::{theme::ColorfulTheme, Select};
-use fs_extra::dir::{copy, CopyOptions};
+use dialoguer::{theme::ColorfulTheme, Confirm};
 use indicatif::{ProgressBar, ProgressStyle};
-use serde::Deserialize;
+use model::variables::check_variables;
+use model::{Hooks, TemplateEntry, TemplateIndex, TemplateManifest};
+use serde_json::{json, Value};
 use std::env;
-use utils::{info, warn};
+use utils::{debug, info, warn};
+use crate::conditions::PathFilter;
 use crate::config;
-
-#[derive(Debug, Clone, Deserialize)]
-pub struct ScaffoldConfig {
-    pub name: String,
-    pub description: Option<String>,
-    pub version: Option<String>,
-    pub author: Option<String>,
-    pub language: Option<String>,
-    pub tags: Option<Vec<String>>,
-    pub path: String,
-}
+use crate::hooks::{hook_env, run_hooks};
+use crate::output::OutputFormat;
+use crate::trust::{template_hash, TrustStore};
+use super::answers::Answers;
+use super::picker;
+use super::plan::Plan;
+use super::prompts;
+use super::NewOptions;
+use super::render::TemplateRenderer;
+use super::staging::Staging;
 
 pub struct NewHandler {
     home_dir: String,
@@ -27,46 +28,73 @@ pub struct NewHandler {
 }
 
 impl NewHandler {
-    pub fn new() -> Self {
-        let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let current_dir = env::current_dir()
-            .unwrap_or_else(|_| Path::new(".").to_path_buf())
-            .to_string_lossy()
-            .to_string();
-        Self { home_dir, current_dir }
+    /// 读取 `home_dir` 下的模板索引和信任记录，在 `current_dir` 中创建项目
+    pub fn new(home_dir: &Path, current_dir: &Path) -> Self {
+        Self {
+            home_dir: home_dir.to_string_lossy().to_string(),
+            current_dir: current_dir.to_string_lossy().to_string(),
+        }
     }
 
-    pub fn execute(&self, template_name: Option<String>, project_name: Option<String>) -> Result<()> {
+    pub fn execute(&self, template_name: Option<String>, project_name: Option<String>, options: NewOptions) -> Result<()> {
         // 日志级别测试消息 - 用于测试日志过滤
 
-        // 1. 读取配置文件
-        let config_file = format!("{}/{}", self.home_dir, config::HOME_SCAFFOLD_CONFIG);
-        let configs = self.read_scaffold_configs(&config_file)?;
-
-        if configs.is_empty() {
-            anyhow::bail!("未找到任何模板配置，请先运行 'statr update' 更新模板仓库");
+        // 0. 读取预先提供的答案
+        let mut answers = Answers::load(options.answers.as_deref(), &options.set, options.yes)?;
+        if options.no_input {
+            answers.disable_prompts();
         }
 
-        // 2. 选择模板
-        let selected_config = if let Some(name) = template_name {
-            self.find_config_by_name(&configs, &name)?
-        } else {
-            self.select_config_interactive(&configs)?
+        // 1~2. 本地目录模板直接读取其 scaffold.json，否则从配置文件中选择模板
+        let selected_config = match template_name.as_deref().and_then(|t| local_template_path(t, &self.home_dir)) {
+            Some(dir) => read_local_config(&Path::new(&self.current_dir).join(dir))?,
+            None => {
+                let config_file = format!("{}/{}", self.home_dir, config::HOME_SCAFFOLD_CONFIG);
+                let configs = self.read_scaffold_configs(&config_file)?;
+
+                if configs.is_empty() {
+                    anyhow::bail!("未找到任何模板配置，请先运行 'statr update' 更新模板仓库");
+                }
+
+                if let Some(name) = template_name {
+                    find_config_by_name(&configs, &name)?
+                } else if answers.interactive() {
+                    self.select_config_interactive(&configs)?
+                } else {
+                    anyhow::bail!("未指定模板：当前不是交互式终端，请使用 --template 指定");
+                }
+            }
         };
 
-        info!(template = %selected_config.name, path = %selected_config.path, "已选择模板");
+        info!(template = %selected_config.manifest.name, path = %selected_config.path, "已选择模板");
 
-        // 3. 获取项目名称
-        let project_name = if let Some(name) = project_name {
+        // 3. 获取项目名称，生成到已有目录时默认使用目录名
+        let default_name = options
+            .into
+            .as_ref()
+            .and_then(|into| Path::new(&self.current_dir).join(into).canonicalize().ok())
+            .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| selected_config.manifest.name.clone());
+        let project_name = if let Some(name) = project_name.or_else(|| answers.get("project_name").map(String::from)) {
             name
+        } else if answers.accept_defaults() {
+            default_name
+        } else if answers.interactive() {
+            self.ask_project_name(&default_name)?
         } else {
-            self.ask_project_name(&selected_config.name)?
+            return Err(answers.missing("project_name", true));
         };
 
         // 4. 检查目标目录
-        let target_dir = format!("{}/{}", self.current_dir, project_name);
-        if Path::new(&target_dir).exists() {
-            anyhow::bail!("目录 {} 已存在", target_dir);
+        let target_dir = match &options.into {
+            Some(into) => Path::new(&self.current_dir).join(into).to_string_lossy().to_string(),
+            None => format!("{}/{}", self.current_dir, project_name),
+        };
+        if options.into.is_none() && Path::new(&target_dir).exists() {
+            anyhow::bail!("目录 {} 已存在，如需生成到已有目录请使用 --into", target_dir);
+        }
+        if options.into.is_some() && Path::new(&target_dir).is_file() {
+            anyhow::bail!("{} 不是目录", target_dir);
         }
 
         // 验证模板路径是否存在
@@ -74,7 +102,64 @@ impl NewHandler {
             anyhow::bail!("模板路径不存在: {}", selected_config.path);
         }
 
-        // 5. 复制模板到目标目录
+        // 5. 询问模板变量
+        check_variables(&selected_config.manifest.variables)
+            .with_context(|| format!("模板 {} 的变量定义无效", selected_config.manifest.name))?;
+        let mut context = self.build_context(&project_name, &selected_config);
+        for key in answers.keys() {
+            if key != "project_name" && !selected_config.manifest.variables.iter().any(|v| v.name == key) {
+                warn!(key = %key, "模板未声明该变量，忽略");
+            }
+        }
+        prompts::resolve_variables(&selected_config.manifest.variables, &mut context, &answers)?;
+
+        // 6. 生成计划
+        let renderer = TemplateRenderer::new(context);
+        let filter = PathFilter::new(&selected_config.manifest.conditions, |expr| renderer.eval_condition(expr))?;
+        let mut plan = Plan::build(
+            &selected_config.manifest.name,
+            &project_name,
+            Path::new(&selected_config.path),
+            Path::new(&target_dir),
+            &renderer,
+            &filter,
+        )?;
+        if !options.no_hooks {
+            // 命令按原样执行，变量通过 SCAFFOLD_<变量名> 环境变量传入，避免答案被拼接进 shell 命令
+            plan.hooks = selected_config.manifest.hooks.clone();
+        }
+
+        if options.dry_run {
+            match options.format {
+                OutputFormat::Text => plan.print_tree(),
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&plan)?),
+            }
+            return Ok(());
+        }
+
+        plan.resolve_conflicts(options.conflict, answers.interactive())?;
+
+        if !plan.hooks.is_empty() && !self.confirm_hooks_trusted(&selected_config, &plan.hooks, &answers, options.trust)? {
+            warn!("未信任模板钩子，本次不执行钩子");
+            plan.hooks = Hooks::default();
+        }
+
+        // 7. 按计划写入，新项目先写入暂存目录，全部完成后再移动到目标位置
+        let staging = match options.into {
+            Some(_) => None,
+            None => Some(Staging::new(Path::new(&target_dir))?),
+        };
+        let work_dir = staging.as_ref().map_or(Path::new(&target_dir), |s| s.dir());
+        // 新项目的 pre_generate 在暂存目录中执行，通过 SCAFFOLD_TARGET_DIR 告知最终位置
+        let mut env = hook_env(renderer.context());
+        env.push(("SCAFFOLD_TARGET_DIR".to_string(), target_dir.clone()));
+
+        if !plan.hooks.pre_generate.is_empty() {
+            fs::create_dir_all(work_dir)
+                .with_context(|| format!("创建目录 {} 失败", work_dir.display()))?;
+            run_hooks("pre_generate", &plan.hooks.pre_generate, work_dir, &env)?;
+        }
+
         let spinner = ProgressBar::new_spinner();
         spinner.set_style(
             ProgressStyle::default_spinner()
@@ -85,63 +170,47 @@ impl NewHandler {
         spinner.set_message(format!("正在复制模板到 {}...", target_dir));
         spinner.enable_steady_tick(std::time::Duration::from_millis(100));
 
-        self.copy_template(&selected_config.path, &target_dir)?;
+        self.copy_template(&plan, work_dir)?;
 
         spinner.finish_with_message(format!("模板已复制到 {}", target_dir));
 
-        // 6. 初始化 git 仓库（可选）
-        info!("正在初始化 Git 仓库...");
-        self.init_git_repo(&target_dir)?;
-        info!("Git 仓库初始化完成");
+        // 8. 初始化 git 仓库（可选），已有仓库时跳过
+        if work_dir.join(".git").exists() {
+            info!("目标目录已是 Git 仓库，跳过初始化");
+        } else {
+            info!("正在初始化 Git 仓库...");
+            self.init_git_repo(&work_dir.to_string_lossy())?;
+            info!("Git 仓库初始化完成");
+        }
+
+        if let Some(staging) = staging {
+            staging.commit()?;
+        }
+
+        // 9. 执行生成后钩子，失败时项目保留，由用户处理
+        if !plan.hooks.post_generate.is_empty() {
+            run_hooks("post_generate", &plan.hooks.post_generate, Path::new(&target_dir), &env)
+                .with_context(|| format!("项目已创建在 {}，但生成后钩子执行失败", target_dir))?;
+        }
 
         info!(project = %project_name, "项目创建完成");
         info!("使用以下命令开始:");
-        info!("  cd {}", project_name);
+        info!("  cd {}", options.into.as_ref().map_or(project_name.clone(), |d| d.display().to_string()));
         info!("  # 查看项目文件");
         Ok(())
     }
 
-    fn read_scaffold_configs(&self, config_file: &str) -> Result<Vec<ScaffoldConfig>> {
-        if !Path::new(config_file).exists() {
-            return Ok(Vec::new());
-        }
-
-        let content = fs::read_to_string(config_file)
-            .with_context(|| format!("读取配置文件 {} 失败", config_file))?;
-
-        let configs: Vec<ScaffoldConfig> = serde_json::from_str(&content)
-            .with_context(|| format!("解析配置文件 {} 失败", config_file))?;
-
-        Ok(configs)
-    }
-
-    fn find_config_by_name(&self, configs: &[ScaffoldConfig], name: &str) -> Result<ScaffoldConfig> {
-        configs
-            .iter()
-            .find(|c| c.name == name)
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("未找到名为 '{}' 的模板", name))
+    fn read_scaffold_configs(&self, config_file: &str) -> Result<Vec<TemplateEntry>> {
+        let index = TemplateIndex::load(Path::new(config_file))?;
+        Ok(index.templates)
     }
 
-    fn select_config_interactive(&self, configs: &[ScaffoldConfig]) -> Result<ScaffoldConfig> {
-        let items: Vec<String> = configs
-            .iter()
-            .map(|c| {
-                format!(
-                    "{} - {}",
-                    c.name.cyan(),
-                    c.description.as_ref().unwrap_or(&"无描述".to_string()).dimmed()
-                )
-            })
-            .collect();
-
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("请选择一个模板")
-            .items(&items)
-            .default(0)
-            .interact()?;
+    fn select_config_interactive(&self, configs: &[TemplateEntry]) -> Result<TemplateEntry> {
+        // 按来源和分类排列，未输入关键字时同一分类的模板相邻
+        let mut configs = configs.to_vec();
+        configs.sort_by(|a, b| (&a.source, &a.category, a.name()).cmp(&(&b.source, &b.category, b.name())));
 
-        Ok(configs[selection].clone())
+        picker::pick(&configs, "请选择一个模板").cloned()
     }
 
     fn ask_project_name(&self, default_name: &str) -> Result<String> {
@@ -155,21 +224,60 @@ impl NewHandler {
         Ok(name)
     }
 
-    fn copy_template(&self, source: &str, target: &str) -> Result<()> {
-        info!(source = %source, target = %target, "开始复制模板");
+    /// 构建模板渲染上下文，模板变量的答案随后写入同一层级
+    fn build_context(&self, project_name: &str, config: &TemplateEntry) -> Value {
+        json!({
+            "project_name": project_name,
+            "template": config,
+        })
+    }
 
-        // 创建目标目录
-        fs::create_dir_all(target)
-            .with_context(|| format!("创建目标目录 {} 失败", target))?;
+    /// 确认模板钩子可以执行
+    ///
+    /// 已信任的模板直接通过；否则列出将执行的命令请用户确认，确认结果写入信任记录。
+    /// 非交互模式下只有传入 `--trust` 才执行未信任的钩子
+    fn confirm_hooks_trusted(&self, config: &TemplateEntry, hooks: &Hooks, answers: &Answers, trust: bool) -> Result<bool> {
+        let source = config.source_url.clone().unwrap_or_else(|| config.path.clone());
+        let hash = template_hash(Path::new(&config.path))?;
+        let trust_file = format!("{}/{}", self.home_dir, config::HOME_SCAFFOLD_TRUST);
+        let mut store = TrustStore::load(Path::new(&trust_file))?;
+
+        if store.is_trusted(&source, &hash) {
+            debug!(template = %config.manifest.name, source = %source, "模板钩子已被信任");
+            return Ok(true);
+        }
 
-        // 复制选项
-        let mut options = CopyOptions::new();
-        options.content_only = true; // 只复制内容，不复制源目录本身
+        println!("{}", format!("模板 {} ({}) 将在本机执行以下命令:", config.manifest.name, source).yellow().bold());
+        for (stage, commands) in [("pre_generate", &hooks.pre_generate), ("post_generate", &hooks.post_generate)] {
+            for command in commands {
+                println!("  {} {}", format!("[{}]", stage).cyan(), command);
+            }
+        }
 
-        copy(source, target, &options)
-            .with_context(|| format!("复制模板从 {} 到 {} 失败", source, target))?;
+        let confirmed = if trust {
+            true
+        } else if answers.interactive() {
+            Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("是否信任该模板并执行这些命令？")
+                .default(false)
+                .interact()?
+        } else {
+            anyhow::bail!(
+                "模板 {} 的钩子尚未被信任：当前不是交互式终端，确认命令安全后使用 --trust 执行，或使用 --no-hooks 跳过",
+                config.manifest.name
+            );
+        };
 
-        Ok(())
+        if confirmed {
+            store.trust(&source, &config.manifest.name, &hash)?;
+            info!(template = %config.manifest.name, "已记录信任");
+        }
+        Ok(confirmed)
+    }
+
+    fn copy_template(&self, plan: &Plan, work_dir: &Path) -> Result<()> {
+        info!(source = %plan.source.display(), target = %work_dir.display(), "开始复制模板");
+        plan.apply(work_dir)
     }
 
     fn init_git_repo(&self, project_dir: &str) -> Result<()> {
@@ -191,8 +299,56 @@ impl NewHandler {
     }
 }
 
+/// 判断模板参数是否指向本地目录：`./`、`../`、`/`、`~/` 开头的路径或 `file://` URL
+pub fn local_template_path(template: &str, home_dir: &str) -> Option<PathBuf> {
+    if let Some(path) = template.strip_prefix("file://") {
+        return Some(PathBuf::from(path));
+    }
+    if let Some(rest) = template.strip_prefix("~/") {
+        return Some(Path::new(home_dir).join(rest));
+    }
+    let is_path = template.starts_with("./")
+        || template.starts_with("../")
+        || Path::new(template).is_absolute();
+    is_path.then(|| PathBuf::from(template))
+}
+
+/// 读取本地目录模板的 scaffold.json
+pub fn read_local_config(dir: &Path) -> Result<TemplateEntry> {
+    let dir = dir
+        .canonicalize()
+        .with_context(|| format!("模板目录 {} 不存在", dir.display()))?;
+    let config_path = dir.join(config::SCAFFOLD_CONFIG_FILE);
+
+    let content = fs::read_to_string(&config_path)
+        .with_context(|| format!("读取文件 {} 失败", config_path.display()))?;
+    let manifest: TemplateManifest = serde_json::from_str(&content)
+        .with_context(|| format!("解析{}失败: {}", config::SCAFFOLD_CONFIG_FILE, config_path.display()))?;
+    manifest.check_version()?;
+
+    Ok(TemplateEntry::local(manifest, &dir))
+}
+
+/// 按名称查找模板
+///
+/// 名称可以带上来源和分类前缀，如 `react-app`、`default/react-app`、
+/// `frontend/react-app` 或 `default/frontend/react-app`，用于区分同名模板
+pub fn find_config_by_name(configs: &[TemplateEntry], name: &str) -> Result<TemplateEntry> {
+    let matches: Vec<&TemplateEntry> = configs.iter().filter(|c| c.matches_name(name)).collect();
+    match matches.as_slice() {
+        [] => anyhow::bail!("未找到名为 '{}' 的模板", name),
+        [config] => Ok((*config).clone()),
+        _ => {
+            let candidates: Vec<String> = matches.iter().map(|c| c.qualified_name()).collect();
+            anyhow::bail!("存在多个名为 '{}' 的模板，请使用以下名称之一: {}", name, candidates.join(", "))
+        }
+    }
+}
+
 impl Default for NewHandler {
     fn default() -> Self {
-        Self::new()
+        let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        let current_dir = env::current_dir().unwrap_or_else(|_| Path::new(".").to_path_buf());
+        Self::new(Path::new(&home_dir), &current_dir)
     }
 }
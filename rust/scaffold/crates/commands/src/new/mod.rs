@@ -1,10 +1,45 @@
+pub mod answers;
+pub mod conflict;
 pub mod handler;
+pub mod picker;
+pub mod plan;
+pub mod prompts;
+pub mod render;
+pub mod staging;
 
+use std::path::PathBuf;
 use anyhow::Result;
+use crate::output::OutputFormat;
+use conflict::ConflictPolicy;
 use handler::NewHandler;
 
+/// `new` 命令的附加选项
+#[derive(Debug, Default)]
+pub struct NewOptions {
+    /// 答案文件（JSON / YAML）
+    pub answers: Option<PathBuf>,
+    /// `--set key=value` 形式的答案
+    pub set: Vec<String>,
+    /// 接受所有默认值，不再询问
+    pub yes: bool,
+    /// 只打印生成计划，不写入文件
+    pub dry_run: bool,
+    /// dry run 的输出格式
+    pub format: OutputFormat,
+    /// 生成到已有目录中，而不是新建 `<项目名称>` 目录
+    pub into: Option<PathBuf>,
+    /// 已有文件内容不同时的处理策略
+    pub conflict: ConflictPolicy,
+    /// 不执行模板钩子
+    pub no_hooks: bool,
+    /// 信任模板并执行其钩子，无需确认
+    pub trust: bool,
+    /// 即使在终端中也不询问，缺少答案时报错
+    pub no_input: bool,
+}
+
 /// 创建新项目
-pub fn new(template_name: Option<String>, project_name: Option<String>) -> Result<()> {
-    let handler = NewHandler::new();
-    handler.execute(template_name, project_name)
+pub fn new(template_name: Option<String>, project_name: Option<String>, options: NewOptions) -> Result<()> {
+    let handler = NewHandler::default();
+    handler.execute(template_name, project_name, options)
 }
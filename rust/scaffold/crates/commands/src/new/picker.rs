@@ -0,0 +1,110 @@
+use anyhow::{Result, Context};
+use colored::Colorize;
+use console::{truncate_str, Key, Term};
+use crate::search::rank::{rank, Match};
+use model::TemplateEntry;
+
+/// 一屏最多显示的模板数
+const PAGE_SIZE: usize = 10;
+
+/// 模糊搜索模板选择器
+///
+/// 输入关键字实时过滤并排序，上下键移动，回车确认，Esc 取消。
+/// 列表下方的预览行显示当前模板的描述、语言和标签
+pub fn pick<'a>(templates: &'a [TemplateEntry], prompt: &str) -> Result<&'a TemplateEntry> {
+    let term = Term::stderr();
+    let mut query = String::new();
+    let mut cursor = 0;
+    let mut rendered = 0;
+
+    term.hide_cursor().ok();
+    let result = loop {
+        let matches = rank(templates, &query);
+        cursor = cursor.min(matches.len().saturating_sub(1));
+
+        term.clear_last_lines(rendered).context("刷新终端失败")?;
+        rendered = render(&term, prompt, &query, &matches, cursor)?;
+
+        match term.read_key().context("读取键盘输入失败")? {
+            Key::Enter => {
+                if let Some(selected) = matches.get(cursor) {
+                    break Ok(selected.template);
+                }
+            }
+            Key::Escape | Key::CtrlC => break Err(anyhow::anyhow!("已取消选择模板")),
+            Key::ArrowUp => cursor = cursor.checked_sub(1).unwrap_or(matches.len().saturating_sub(1)),
+            Key::ArrowDown | Key::Tab => cursor = if cursor + 1 < matches.len() { cursor + 1 } else { 0 },
+            Key::Backspace => {
+                query.pop();
+                cursor = 0;
+            }
+            Key::Char(c) if !c.is_control() => {
+                query.push(c);
+                cursor = 0;
+            }
+            _ => {}
+        }
+    };
+
+    term.clear_last_lines(rendered).ok();
+    term.show_cursor().ok();
+    if let Ok(template) = result {
+        term.write_line(&format!("{} {} · {}", "✔".green(), prompt.bold(), template.display_name().cyan()))
+            .ok();
+    }
+    result
+}
+
+/// 绘制输入行、当前页的模板和预览行，返回绘制的行数
+fn render(term: &Term, prompt: &str, query: &str, matches: &[Match], cursor: usize) -> Result<usize> {
+    let width = term.size().1 as usize;
+    let mut lines = Vec::new();
+
+    let input = if query.is_empty() {
+        "输入关键字搜索".dimmed().to_string()
+    } else {
+        query.to_string()
+    };
+    lines.push(format!("{} {} › {}", "?".yellow(), prompt.bold(), input));
+
+    // 光标所在的一页
+    let start = (cursor / PAGE_SIZE) * PAGE_SIZE;
+    for (index, m) in matches.iter().enumerate().skip(start).take(PAGE_SIZE) {
+        let category = m.template.category.as_ref().map(|c| format!("[{}] ", c)).unwrap_or_default();
+        let name = format!("{}{}", category.yellow(), m.template.display_name());
+        lines.push(if index == cursor {
+            format!("{} {}", "❯".cyan(), name.cyan())
+        } else {
+            format!("  {}", name)
+        });
+    }
+
+    let preview = match matches.get(cursor) {
+        Some(m) => preview_line(m.template),
+        None => "没有匹配的模板".red().to_string(),
+    };
+    let position = if matches.len() > PAGE_SIZE {
+        format!(" ({}/{})", cursor + 1, matches.len())
+    } else {
+        String::new()
+    };
+    lines.push(format!("  {}{}", preview, position.dimmed()));
+
+    for line in &lines {
+        term.write_line(&truncate_str(line, width, "…")).context("写入终端失败")?;
+    }
+    Ok(lines.len())
+}
+
+/// 预览行：描述 · 语言 · 标签
+fn preview_line(template: &TemplateEntry) -> String {
+    let parts: Vec<String> = [
+        Some(template.manifest.description.clone().unwrap_or_else(|| "无描述".to_string())),
+        template.manifest.language.clone(),
+        template.manifest.tags.as_ref().filter(|t| !t.is_empty()).map(|t| t.join(", ")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    parts.join(" · ").dimmed().to_string()
+}
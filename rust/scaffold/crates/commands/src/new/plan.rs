@@ -0,0 +1,323 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{Result, Context};
+use colored::Colorize;
+use serde::Serialize;
+use walkdir::WalkDir;
+use utils::{debug, info, size::format_size};
+use crate::conditions::PathFilter;
+use crate::config;
+use crate::template::snapshot::is_fixture_path;
+use model::Hooks;
+use super::conflict::{self, ConflictPolicy, Resolution, SIDECAR_SUFFIX};
+use super::render::{text_content, TemplateRenderer};
+
+/// 对单个路径的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    /// 创建目录
+    Mkdir,
+    /// 经过 Handlebars 渲染后写入
+    Render,
+    /// 原样复制（二进制文件或不含模板标记的文本）
+    Copy,
+    /// 条件不满足，跳过
+    Skip,
+}
+
+/// 计划中的一项
+#[derive(Debug, Serialize)]
+pub struct PlanEntry {
+    /// 模板中的相对路径
+    pub source: PathBuf,
+    /// 生成到项目中的相对路径
+    pub target: PathBuf,
+    pub action: Action,
+    /// 写入后的大小，目录和跳过的路径为 0
+    pub size: u64,
+    /// 文件名或目录名是否经过模板渲染而改变
+    pub renamed: bool,
+    /// 目标位置已有内容不同的文件
+    pub conflict: bool,
+    /// 冲突的处理结果，由 resolve_conflicts 填写
+    #[serde(skip)]
+    pub resolution: Option<Resolution>,
+    /// 跳过的原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// 渲染后的内容，仅 Render 有
+    #[serde(skip)]
+    pub content: Option<String>,
+}
+
+/// 生成计划
+///
+/// 描述模板中每个路径将被如何处理，dry run 打印计划，正式运行按计划写入
+#[derive(Debug, Serialize)]
+pub struct Plan {
+    pub template: String,
+    pub project_name: String,
+    /// 模板目录
+    pub source: PathBuf,
+    /// 目标目录
+    pub target: PathBuf,
+    pub entries: Vec<PlanEntry>,
+    /// 将要执行的钩子（已展开变量）
+    #[serde(skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
+}
+
+impl Plan {
+    /// 遍历模板目录生成计划，文本文件在此时完成渲染
+    pub fn build(
+        template: &str,
+        project_name: &str,
+        source: &Path,
+        target: &Path,
+        renderer: &TemplateRenderer,
+        filter: &PathFilter,
+    ) -> Result<Self> {
+        let mut entries = Vec::new();
+
+        // 模板自身的 Git 仓库不属于项目，否则新项目会带上模板的提交历史
+        let mut walker = WalkDir::new(source)
+            .min_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.file_name() != ".git");
+        while let Some(entry) = walker.next() {
+            let entry = entry.with_context(|| format!("遍历模板目录 {} 失败", source.display()))?;
+            let relative = entry.path().strip_prefix(source)
+                .with_context(|| format!("计算 {} 的相对路径失败", entry.path().display()))?
+                .to_path_buf();
+            let is_dir = entry.file_type().is_dir();
+
+            // 模板清单只描述模板，不属于项目
+            if relative == Path::new(config::SCAFFOLD_CONFIG_FILE) {
+                continue;
+            }
+
+            // 模板自身的测试用例和快照同样只属于模板
+            if is_fixture_path(source, &relative) {
+                if is_dir {
+                    walker.skip_current_dir();
+                }
+                continue;
+            }
+
+            // 条件不满足时跳过，目录连同其内容一起跳过
+            if let Some(rule) = filter.excluded_by(&relative, is_dir) {
+                debug!(path = %relative.display(), rule = %rule.path, when = %rule.when, "条件不满足，跳过");
+                if is_dir {
+                    walker.skip_current_dir();
+                }
+                entries.push(PlanEntry {
+                    target: relative.clone(),
+                    source: relative,
+                    action: Action::Skip,
+                    size: 0,
+                    renamed: false,
+                    conflict: false,
+                    resolution: None,
+                    reason: Some(format!("{} => {}", rule.path, rule.when)),
+                    content: None,
+                });
+                continue;
+            }
+
+            let rendered = renderer.render_path(&relative)?;
+            if rendered != relative {
+                debug!(from = %relative.display(), to = %rendered.display(), "重命名模板路径");
+            }
+            // 只标记自身名称发生变化的路径，父目录重命名不计入
+            let renamed = rendered.file_name() != relative.file_name();
+
+            let (action, size, content, conflict) = if is_dir {
+                (Action::Mkdir, 0, None, false)
+            } else {
+                let bytes = fs::read(entry.path())
+                    .with_context(|| format!("读取文件 {} 失败", entry.path().display()))?;
+                let (action, content) = match text_content(&bytes) {
+                    Some(text) if text.contains("{{") => {
+                        let content = renderer.render_str(text, &relative.display().to_string())?;
+                        (Action::Render, Some(content))
+                    }
+                    _ => (Action::Copy, None),
+                };
+                let generated = content.as_ref().map_or(&bytes[..], |c| c.as_bytes());
+                let existing = target.join(&rendered);
+                // 已有目录等非文件无法按冲突策略处理，直接报错，避免模板文件被静默丢弃
+                if existing.exists() && !existing.is_file() {
+                    anyhow::bail!("{} 已存在且不是文件，无法写入模板文件 {}", existing.display(), relative.display());
+                }
+                let conflict = existing.is_file()
+                    && fs::read(&existing)
+                        .with_context(|| format!("读取文件 {} 失败", existing.display()))?
+                        != generated;
+                (action, generated.len() as u64, content, conflict)
+            };
+
+            entries.push(PlanEntry {
+                source: relative,
+                target: rendered,
+                action,
+                size,
+                renamed,
+                conflict,
+                resolution: None,
+                reason: None,
+                content,
+            });
+        }
+
+        Ok(Self {
+            template: template.to_string(),
+            project_name: project_name.to_string(),
+            source: source.to_path_buf(),
+            target: target.to_path_buf(),
+            entries,
+            hooks: Hooks::default(),
+        })
+    }
+
+    /// 按策略决定每个冲突文件的处理方式，需要询问时在此完成
+    pub fn resolve_conflicts(&mut self, policy: ConflictPolicy, interactive: bool) -> Result<()> {
+        for entry in self.entries.iter_mut().filter(|e| e.conflict) {
+            let existing_path = self.target.join(&entry.target);
+            let existing = fs::read(&existing_path)
+                .with_context(|| format!("读取文件 {} 失败", existing_path.display()))?;
+            let generated = generated_content(&self.source, entry)?;
+            entry.resolution = Some(conflict::resolve(policy, &entry.target, &existing, &generated, interactive)?);
+        }
+        Ok(())
+    }
+
+    /// 按计划写入 root 目录，root 为目标目录本身或它的暂存目录
+    ///
+    /// 目标文件已存在时，内容相同直接跳过，内容不同按 resolve_conflicts 的结果处理，未处理的冲突保留已有文件
+    pub fn apply(&self, root: &Path) -> Result<()> {
+        fs::create_dir_all(root)
+            .with_context(|| format!("创建目标目录 {} 失败", root.display()))?;
+
+        for entry in &self.entries {
+            let source = self.source.join(&entry.source);
+            let mut target = root.join(&entry.target);
+            match entry.action {
+                Action::Mkdir => {
+                    fs::create_dir_all(&target)
+                        .with_context(|| format!("创建目录 {} 失败", target.display()))?;
+                }
+                Action::Render | Action::Copy => {
+                    if target.is_file() {
+                        match entry.resolution {
+                            _ if !entry.conflict => {
+                                debug!(file = %entry.target.display(), "内容相同，跳过");
+                                continue;
+                            }
+                            Some(Resolution::Overwrite) => {
+                                info!(file = %entry.target.display(), "覆盖已有文件");
+                            }
+                            Some(Resolution::Sidecar) => {
+                                let mut name = target.file_name().unwrap_or_default().to_os_string();
+                                name.push(SIDECAR_SUFFIX);
+                                target.set_file_name(name);
+                                info!(file = %target.display(), "生成的内容已写入");
+                            }
+                            Some(Resolution::Skip) | None => {
+                                info!(file = %entry.target.display(), "文件已存在，保留已有文件");
+                                continue;
+                            }
+                        }
+                    }
+
+                    fs::write(&target, generated_content(&self.source, entry)?)
+                        .with_context(|| format!("写入文件 {} 失败", target.display()))?;
+                    // 保留源文件权限，例如脚本的可执行位
+                    let permissions = fs::metadata(&source)
+                        .with_context(|| format!("读取文件 {} 的元数据失败", source.display()))?
+                        .permissions();
+                    fs::set_permissions(&target, permissions)
+                        .with_context(|| format!("设置文件 {} 的权限失败", target.display()))?;
+                    debug!(file = %entry.target.display(), action = ?entry.action, "已写入文件");
+                }
+                Action::Skip => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 将生成的文件总大小
+    pub fn total_size(&self) -> u64 {
+        self.entries.iter().map(|e| e.size).sum()
+    }
+
+    /// 统计某种处理方式的数量
+    pub fn count(&self, action: Action) -> usize {
+        self.entries.iter().filter(|e| e.action == action).count()
+    }
+
+    /// 以文件树形式打印计划
+    pub fn print_tree(&self) {
+        println!("{} {}", self.project_name.bold(), format!("({})", self.target.display()).dimmed());
+
+        for entry in &self.entries {
+            let depth = entry.target.components().count().saturating_sub(1);
+            let indent = "  ".repeat(depth + 1);
+            let name = entry
+                .target
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let line = match entry.action {
+                Action::Mkdir => format!("{}/", name).blue().bold().to_string(),
+                Action::Render => format!("{} {} {}", name, format_size(entry.size).dimmed(), "[模板]".green()),
+                Action::Copy => format!("{} {}", name, format_size(entry.size).dimmed()),
+                Action::Skip => format!(
+                    "{} {}",
+                    name.dimmed(),
+                    format!("[跳过: {}]", entry.reason.as_deref().unwrap_or_default()).yellow()
+                ),
+            };
+
+            let mut line = format!("{}{}", indent, line);
+            if entry.renamed {
+                line = format!("{} {}", line, format!("[重命名自 {}]", entry.source.display()).cyan());
+            }
+            if entry.conflict {
+                line = format!("{} {}", line, "[冲突]".red());
+            }
+            println!("{}", line);
+        }
+
+        println!();
+        println!(
+            "共 {} 个文件，{}；渲染 {} 个，原样复制 {} 个，跳过 {} 个，冲突 {} 个",
+            self.count(Action::Render) + self.count(Action::Copy),
+            format_size(self.total_size()),
+            self.count(Action::Render),
+            self.count(Action::Copy),
+            self.count(Action::Skip),
+            self.entries.iter().filter(|e| e.conflict).count()
+        );
+
+        for (stage, commands) in [("pre_generate", &self.hooks.pre_generate), ("post_generate", &self.hooks.post_generate)] {
+            for command in commands {
+                println!("{} {}", format!("[{}]", stage).cyan(), command);
+            }
+        }
+    }
+}
+
+/// 条目生成的文件内容：渲染结果或模板原文件
+fn generated_content(source: &Path, entry: &PlanEntry) -> Result<Vec<u8>> {
+    match &entry.content {
+        Some(content) => Ok(content.clone().into_bytes()),
+        None => {
+            let path = source.join(&entry.source);
+            fs::read(&path).with_context(|| format!("读取文件 {} 失败", path.display()))
+        }
+    }
+}
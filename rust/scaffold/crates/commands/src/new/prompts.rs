@@ -0,0 +1,114 @@
+use anyhow::Result;
+use colored::Colorize;
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
+use serde_json::Value;
+use model::{TemplateVariable, VariableKind};
+use super::answers::Answers;
+use super::render::TemplateRenderer;
+
+/// 依次确定模板变量的值，写入渲染上下文
+///
+/// 优先使用预先提供的答案；`--yes` 时使用默认值；其余情况在终端中询问，
+/// 不是终端时报错并指出缺少的变量
+pub fn resolve_variables(variables: &[TemplateVariable], context: &mut Value, answers: &Answers) -> Result<()> {
+    for variable in variables {
+        let value = if let Some(input) = answers.get(&variable.name) {
+            variable.parse_value(input)?
+        } else if answers.accept_defaults() || !answers.interactive() {
+            match default_value(variable, context)? {
+                Some(default) if answers.accept_defaults() => default,
+                _ if answers.interactive() => ask_variable(variable, context)?,
+                default => return Err(answers.missing(&variable.name, default.is_some())),
+            }
+        } else {
+            ask_variable(variable, context)?
+        };
+        if let Value::Object(map) = context {
+            map.insert(variable.name.clone(), value);
+        }
+    }
+    Ok(())
+}
+
+/// 变量的默认值，布尔和多选在未声明默认值时分别为 false 和空数组
+fn default_value(variable: &TemplateVariable, context: &Value) -> Result<Option<Value>> {
+    let value = match &variable.kind {
+        VariableKind::String { default } => match default {
+            Some(default) => {
+                let renderer = TemplateRenderer::new(context.clone());
+                Some(variable.parse_value(&renderer.render_str(default, &variable.name)?)?)
+            }
+            None => None,
+        },
+        VariableKind::Bool { default } => Some(Value::Bool(default.unwrap_or(false))),
+        VariableKind::Number { default } => default.clone().map(Value::Number),
+        VariableKind::Select { default, .. } => default.clone().map(Value::String),
+        VariableKind::Multiselect { default, .. } => Some(Value::Array(
+            default.iter().flatten().cloned().map(Value::String).collect(),
+        )),
+    };
+    Ok(value)
+}
+
+fn ask_variable(variable: &TemplateVariable, context: &Value) -> Result<Value> {
+    let theme = ColorfulTheme::default();
+    let prompt = match &variable.help {
+        Some(help) => format!("{} {}", variable.prompt_text(), format!("({})", help).dimmed()),
+        None => variable.prompt_text().to_string(),
+    };
+
+    let value = match &variable.kind {
+        VariableKind::String { default } => {
+            let mut input = Input::<String>::with_theme(&theme)
+                .with_prompt(prompt)
+                .validate_with(|v: &String| variable.parse_value(v).map(|_| ()).map_err(|e| e.to_string()));
+            if let Some(default) = default {
+                let renderer = TemplateRenderer::new(context.clone());
+                input = input.default(renderer.render_str(default, &variable.name)?);
+            }
+            variable.parse_value(&input.interact_text()?)?
+        }
+        VariableKind::Bool { default } => {
+            let answer = Confirm::with_theme(&theme)
+                .with_prompt(prompt)
+                .default(default.unwrap_or(false))
+                .interact()?;
+            Value::Bool(answer)
+        }
+        VariableKind::Number { default } => {
+            let mut input = Input::<String>::with_theme(&theme)
+                .with_prompt(prompt)
+                .validate_with(|v: &String| variable.parse_value(v).map(|_| ()).map_err(|e| e.to_string()));
+            if let Some(default) = default {
+                input = input.default(default.to_string());
+            }
+            variable.parse_value(&input.interact_text()?)?
+        }
+        VariableKind::Select { options, default } => {
+            let index = default
+                .as_ref()
+                .and_then(|d| options.iter().position(|o| o == d))
+                .unwrap_or(0);
+            let selection = Select::with_theme(&theme)
+                .with_prompt(prompt)
+                .items(options)
+                .default(index)
+                .interact()?;
+            Value::String(options[selection].clone())
+        }
+        VariableKind::Multiselect { options, default } => {
+            let defaults: Vec<bool> = options
+                .iter()
+                .map(|o| default.as_ref().is_some_and(|d| d.contains(o)))
+                .collect();
+            let selections = MultiSelect::with_theme(&theme)
+                .with_prompt(prompt)
+                .items(options)
+                .defaults(&defaults)
+                .interact()?;
+            Value::Array(selections.into_iter().map(|i| Value::String(options[i].clone())).collect())
+        }
+    };
+
+    Ok(value)
+}
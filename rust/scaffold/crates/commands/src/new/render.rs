@@ -0,0 +1,137 @@
+use std::path::{Component, Path, PathBuf};
+use anyhow::{Result, Context};
+use handlebars::{handlebars_helper, Handlebars};
+use serde_json::Value;
+
+/// 判断二进制文件时检查的字节数
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// 模板中可用的 helper：Handlebars 内置 helper、大小写转换 helper 和 `contains`
+pub const HELPERS: &[&str] = &[
+    "if", "unless", "each", "with", "lookup", "raw", "log",
+    "eq", "ne", "gt", "gte", "lt", "lte", "and", "or", "not", "len",
+    "lowerCamelCase", "upperCamelCase", "snakeCase", "kebabCase",
+    "shoutySnakeCase", "shoutyKebabCase", "titleCase", "trainCase",
+    "contains",
+];
+
+// 判断数组是否包含某个值，字符串则判断是否包含子串，用于多选变量
+handlebars_helper!(contains: |list: Json, item: Json| match list {
+    Value::Array(items) => items.contains(item),
+    Value::String(text) => item.as_str().is_some_and(|i| text.contains(i)),
+    _ => false,
+});
+
+/// 模板渲染器
+///
+/// 使用 Handlebars 渲染模板中的文本文件内容和路径
+pub struct TemplateRenderer {
+    engine: Handlebars<'static>,
+    context: Value,
+}
+
+impl TemplateRenderer {
+    pub fn new(context: Value) -> Self {
+        let mut engine = Handlebars::new();
+        // 生成的是源码而不是 HTML，不做转义
+        engine.register_escape_fn(handlebars::no_escape);
+        engine.register_helper("contains", Box::new(contains));
+        Self { engine, context }
+    }
+
+    /// 渲染上下文
+    pub fn context(&self) -> &Value {
+        &self.context
+    }
+
+    /// 渲染一段模板字符串，name 用于错误提示
+    pub fn render_str(&self, template: &str, name: &str) -> Result<String> {
+        self.engine
+            .render_template(template, &self.context)
+            .with_context(|| format!("渲染模板 {} 失败", name))
+    }
+
+    /// 按 Handlebars 的 `#if` 规则对表达式求值
+    pub fn eval_condition(&self, expr: &str) -> Result<bool> {
+        Ok(self.render_str(&condition_template(expr), expr)? == "true")
+    }
+
+    /// 逐段渲染相对路径，例如 `src/{{crate_name}}/lib.rs`
+    ///
+    /// 渲染后的文件名不能为空，也不能包含 `..` 或路径分隔符
+    pub fn render_path(&self, relative: &Path) -> Result<PathBuf> {
+        let mut rendered = PathBuf::new();
+        for component in relative.components() {
+            let Component::Normal(segment) = component else {
+                anyhow::bail!("模板路径 {} 不是合法的相对路径", relative.display());
+            };
+            match segment.to_str() {
+                Some(segment) if segment.contains("{{") => {
+                    let name = self.render_str(segment, &relative.display().to_string())?;
+                    if name.trim().is_empty() {
+                        anyhow::bail!("模板路径 {} 中的 {} 渲染后为空", relative.display(), segment);
+                    }
+                    if name.contains("..") || name.contains('/') || name.contains('\\') {
+                        anyhow::bail!(
+                            "模板路径 {} 中的 {} 渲染为非法文件名 {}",
+                            relative.display(),
+                            segment,
+                            name
+                        );
+                    }
+                    rendered.push(name);
+                }
+                _ => rendered.push(segment),
+            }
+        }
+        Ok(rendered)
+    }
+}
+
+/// 条件表达式求值时使用的模板
+pub fn condition_template(expr: &str) -> String {
+    format!("{{{{#if {}}}}}true{{{{else}}}}false{{{{/if}}}}", expr)
+}
+
+/// 如果内容是文本则返回字符串，二进制内容返回 None
+///
+/// 开头出现 NUL 字节或不是合法 UTF-8 的文件视为二进制文件
+pub fn text_content(bytes: &[u8]) -> Option<&str> {
+    let head = &bytes[..bytes.len().min(BINARY_SNIFF_LEN)];
+    if head.contains(&0) {
+        return None;
+    }
+    std::str::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn renderer() -> TemplateRenderer {
+        TemplateRenderer::new(json!({ "project_name": "my-app", "empty": "", "parent": "..", "nested": "a/b" }))
+    }
+
+    #[test]
+    fn render_path_renders_each_segment() {
+        let rendered = renderer().render_path(Path::new("src/{{snakeCase project_name}}/{{project_name}}.rs")).unwrap();
+        assert_eq!(rendered, Path::new("src/my_app/my-app.rs"));
+        assert_eq!(renderer().render_path(Path::new("plain/file.txt")).unwrap(), Path::new("plain/file.txt"));
+    }
+
+    #[test]
+    fn render_path_rejects_empty_names() {
+        let error = renderer().render_path(Path::new("src/{{empty}}/lib.rs")).unwrap_err();
+        assert!(error.to_string().contains("渲染后为空"), "{:#}", error);
+    }
+
+    #[test]
+    fn render_path_rejects_parent_and_separators() {
+        for path in ["{{parent}}/escape.txt", "{{nested}}.txt", "x{{parent}}x"] {
+            let error = renderer().render_path(Path::new(path)).unwrap_err();
+            assert!(error.to_string().contains("非法文件名"), "{}: {:#}", path, error);
+        }
+        assert!(renderer().render_path(Path::new("../escape.txt")).is_err());
+    }
+}
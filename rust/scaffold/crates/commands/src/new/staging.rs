@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once};
+use anyhow::{Result, Context};
+use utils::{debug, warn};
+use crate::config;
+
+/// 当前正在使用的暂存目录，Ctrl-C 时由信号处理函数清理
+static ACTIVE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+static HANDLER: Once = Once::new();
+
+/// 项目暂存目录
+///
+/// 项目先生成到目标目录旁边的暂存目录，全部成功后再重命名为目标目录。
+/// 未提交就被丢弃（出错返回或 Ctrl-C）时删除暂存目录，不留下半成品
+pub struct Staging {
+    dir: PathBuf,
+    target: PathBuf,
+    committed: bool,
+}
+
+impl Staging {
+    /// 在目标目录旁创建暂存目录，同名的残留暂存目录会先被删除
+    pub fn new(target: &Path) -> Result<Self> {
+        let name = target
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("无效的目标目录 {}", target.display()))?;
+        let dir = target.with_file_name(format!(
+            ".{}{}",
+            name.to_string_lossy(),
+            config::STAGING_DIR_SUFFIX
+        ));
+
+        if dir.exists() {
+            warn!(dir = %dir.display(), "删除上次残留的暂存目录");
+            fs::remove_dir_all(&dir)
+                .with_context(|| format!("删除暂存目录 {} 失败", dir.display()))?;
+        }
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("创建暂存目录 {} 失败", dir.display()))?;
+        debug!(dir = %dir.display(), "已创建暂存目录");
+
+        install_signal_handler();
+        *ACTIVE.lock().unwrap_or_else(|e| e.into_inner()) = Some(dir.clone());
+
+        Ok(Self {
+            dir,
+            target: target.to_path_buf(),
+            committed: false,
+        })
+    }
+
+    /// 暂存目录路径
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 将暂存目录重命名为目标目录
+    pub fn commit(mut self) -> Result<()> {
+        if self.target.exists() {
+            anyhow::bail!("目录 {} 已存在", self.target.display());
+        }
+        fs::rename(&self.dir, &self.target).with_context(|| {
+            format!("重命名目录从 {} 到 {} 失败", self.dir.display(), self.target.display())
+        })?;
+        self.committed = true;
+        Ok(())
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        *ACTIVE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        if !self.committed && self.dir.exists() {
+            debug!(dir = %self.dir.display(), "清理暂存目录");
+            if let Err(e) = fs::remove_dir_all(&self.dir) {
+                warn!(dir = %self.dir.display(), error = %e, "清理暂存目录失败");
+            }
+        }
+    }
+}
+
+/// 注册 Ctrl-C 处理函数：删除暂存目录后以 130 退出
+fn install_signal_handler() {
+    HANDLER.call_once(|| {
+        let result = ctrlc::set_handler(|| {
+            if let Some(dir) = ACTIVE.lock().unwrap_or_else(|e| e.into_inner()).take() {
+                let _ = fs::remove_dir_all(dir);
+            }
+            std::process::exit(130);
+        });
+        if let Err(e) = result {
+            warn!(error = %e, "注册 Ctrl-C 处理函数失败");
+        }
+    });
+}
@@ -0,0 +1,86 @@
+//! 命令输出格式
+
+use clap::ValueEnum;
+use colored::{Color, Colorize};
+use unicode_width::UnicodeWidthStr;
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// 适合阅读的彩色文本
+    #[default]
+    Text,
+    /// JSON，便于脚本处理
+    Json,
+}
+
+/// 按显示宽度对齐的文本表格
+///
+/// 汉字等宽字符按两列计算，最后一列不补空格
+pub struct Table {
+    headers: Vec<&'static str>,
+    colors: Vec<Option<Color>>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&'static str]) -> Self {
+        Self {
+            headers: headers.to_vec(),
+            colors: vec![None; headers.len()],
+            rows: Vec::new(),
+        }
+    }
+
+    /// 设置某一列的颜色
+    pub fn color(mut self, column: usize, color: Color) -> Self {
+        self.colors[column] = Some(color);
+        self
+    }
+
+    pub fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// 打印表头和所有行，每行缩进两个空格
+    pub fn print(&self) {
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .filter_map(|r| r.get(i))
+                    .map(|c| c.width())
+                    .chain(std::iter::once(self.headers[i].width()))
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let header: Vec<String> = self.headers.iter().map(|h| h.to_string()).collect();
+        println!("  {}", self.format_row(&header, &widths, true).bold());
+        for row in &self.rows {
+            println!("  {}", self.format_row(row, &widths, false));
+        }
+    }
+
+    fn format_row(&self, cells: &[String], widths: &[usize], header: bool) -> String {
+        let last = widths.len().saturating_sub(1);
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                let padding = if i == last { 0 } else { widths[i].saturating_sub(cell.width()) };
+                let cell = match self.colors[i] {
+                    Some(color) if !header => cell.color(color).to_string(),
+                    _ => cell.clone(),
+                };
+                format!("{}{}", cell, " ".repeat(padding))
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+}
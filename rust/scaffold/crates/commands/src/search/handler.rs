@@ -0,0 +1,53 @@
+use std::path::Path;
+use anyhow::{Result, Context};
+use colored::Colorize;
+use model::{TemplateEntry, TemplateIndex};
+use crate::config;
+use crate::list::handler::template_table;
+use crate::output::OutputFormat;
+use super::rank::rank;
+
+pub struct SearchHandler {
+    home_dir: String,
+}
+
+impl SearchHandler {
+    pub fn new() -> Self {
+        let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        Self { home_dir }
+    }
+
+    pub fn execute(&self, query: &str, format: OutputFormat) -> Result<()> {
+        let config_file = format!("{}/{}", self.home_dir, config::HOME_SCAFFOLD_CONFIG);
+        let index = TemplateIndex::load(Path::new(&config_file))?;
+        if index.templates.is_empty() {
+            if format == OutputFormat::Json {
+                println!("[]");
+            }
+            eprintln!("未找到模板索引，请先运行 {} 下载模板", "scaffold update".cyan());
+            return Ok(());
+        }
+
+        let matches = rank(&index.templates, query);
+        let templates: Vec<&TemplateEntry> = matches.iter().map(|m| m.template).collect();
+
+        if format == OutputFormat::Json {
+            let json = serde_json::to_string_pretty(&templates).with_context(|| "序列化搜索结果失败")?;
+            println!("{}", json);
+            return Ok(());
+        }
+
+        if templates.is_empty() {
+            println!("没有与 {} 匹配的模板", query.cyan());
+            return Ok(());
+        }
+        template_table(&templates).print();
+        Ok(())
+    }
+}
+
+impl Default for SearchHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
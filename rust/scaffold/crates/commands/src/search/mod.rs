@@ -0,0 +1,12 @@
+pub mod handler;
+pub mod rank;
+
+use anyhow::Result;
+use handler::SearchHandler;
+use crate::output::OutputFormat;
+
+/// 按关键字搜索模板
+pub fn search(query: String, format: OutputFormat) -> Result<()> {
+    let handler = SearchHandler::new();
+    handler.execute(&query, format)
+}
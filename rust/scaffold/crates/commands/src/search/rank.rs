@@ -0,0 +1,99 @@
+//! 模板模糊搜索排序
+//!
+//! 交互式选择和 `scaffold search` 使用相同的排序规则
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use model::TemplateEntry;
+
+/// 名称匹配的权重，名称命中应排在描述命中之前
+const NAME_WEIGHT: i64 = 3;
+/// 标签和语言匹配的权重
+const TAG_WEIGHT: i64 = 2;
+
+/// 一条搜索结果
+#[derive(Debug, Clone, Copy)]
+pub struct Match<'a> {
+    pub template: &'a TemplateEntry,
+    pub score: i64,
+}
+
+/// 按查询对模板排序，返回匹配的模板，得分高的在前
+///
+/// 查询按空白拆分为多个关键字，每个关键字都要命中名称、描述、标签、语言或分类之一，
+/// 得分为各关键字在加权后最佳字段上的得分之和。查询为空时按原顺序返回全部模板
+pub fn rank<'a>(templates: &'a [TemplateEntry], query: &str) -> Vec<Match<'a>> {
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let keywords: Vec<&str> = query.split_whitespace().collect();
+
+    let mut matches: Vec<Match<'a>> = templates
+        .iter()
+        .filter_map(|template| {
+            let score = keywords
+                .iter()
+                .map(|keyword| best_score(&matcher, template, keyword))
+                .sum::<Option<i64>>()?;
+            Some(Match { template, score })
+        })
+        .collect();
+
+    // 稳定排序，同分时保持原顺序
+    matches.sort_by_key(|m| std::cmp::Reverse(m.score));
+    matches
+}
+
+/// 关键字在模板各字段上的最高加权得分，都不命中时返回 None
+fn best_score(matcher: &SkimMatcherV2, template: &TemplateEntry, keyword: &str) -> Option<i64> {
+    let weighted = |text: &str, weight: i64| matcher.fuzzy_match(text, keyword).map(|s| s * weight);
+
+    let manifest = &template.manifest;
+    std::iter::once(weighted(&manifest.name, NAME_WEIGHT))
+        .chain(manifest.tags.iter().flatten().map(|t| weighted(t, TAG_WEIGHT)))
+        .chain(manifest.language.as_deref().map(|l| weighted(l, TAG_WEIGHT)))
+        .chain(template.category.as_deref().map(|c| weighted(c, 1)))
+        .chain(manifest.description.as_deref().map(|d| weighted(d, 1)))
+        .flatten()
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn templates() -> Vec<TemplateEntry> {
+        [
+            json!({ "name": "docs-site", "description": "React documentation site", "path": "" }),
+            json!({ "name": "react-app", "language": "TypeScript", "tags": ["web"], "path": "" }),
+            json!({ "name": "axum-api", "language": "Rust", "tags": ["web", "api"], "category": "backend", "path": "" }),
+            json!({ "name": "rust-cli", "language": "Rust", "tags": ["cli"], "path": "" }),
+        ]
+        .into_iter()
+        .map(|t| serde_json::from_value(t).unwrap())
+        .collect()
+    }
+
+    fn names(templates: &[TemplateEntry], query: &str) -> Vec<String> {
+        rank(templates, query).iter().map(|m| m.template.manifest.name.clone()).collect()
+    }
+
+    #[test]
+    fn empty_query_keeps_order() {
+        let templates = templates();
+        assert_eq!(names(&templates, "  "), ["docs-site", "react-app", "axum-api", "rust-cli"]);
+    }
+
+    #[test]
+    fn name_matches_rank_before_description() {
+        let templates = templates();
+        assert_eq!(names(&templates, "react"), ["react-app", "docs-site"]);
+    }
+
+    #[test]
+    fn every_keyword_must_match() {
+        let templates = templates();
+        assert_eq!(names(&templates, "rust web"), ["axum-api"]);
+        assert_eq!(names(&templates, "backend"), ["axum-api"]);
+        assert!(names(&templates, "rust zzz").is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use std::path::Path;
+use anyhow::{Result, Context};
+use colored::Colorize;
+use fs_extra::dir::remove;
+use model::index::{check_source_name, short_commit};
+use model::{Source, TemplateEntry, TemplateIndex};
+use utils::info;
+use crate::config;
+
+pub struct SourceHandler {
+    home_dir: String,
+}
+
+impl SourceHandler {
+    pub fn new() -> Self {
+        let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        Self { home_dir }
+    }
+
+    pub fn add(&self, name: &str, url: &str, reference: Option<&str>) -> Result<()> {
+        check_source_name(name)?;
+
+        let config_file = self.config_file();
+        let mut index = TemplateIndex::load(Path::new(&config_file))?;
+        let mut sources = index.sources();
+        if sources.iter().any(|s| s.name == name) {
+            anyhow::bail!("来源 {} 已存在", name);
+        }
+
+        let mut source = Source::parse(name, url);
+        if let Some(reference) = reference {
+            source.reference = Some(reference.to_string());
+        }
+        info!(name = %name, url = %source.url, reference = ?source.reference, "已添加模板来源");
+        sources.push(source);
+        index.sources = Some(sources);
+        index.save(Path::new(&config_file))?;
+
+        println!("已添加来源 {}，运行 {} 拉取模板", name.cyan(), "scaffold update".cyan());
+        Ok(())
+    }
+
+    pub fn remove(&self, name: &str) -> Result<()> {
+        let config_file = self.config_file();
+        let mut index = TemplateIndex::load(Path::new(&config_file))?;
+        let mut sources = index.sources();
+        if !sources.iter().any(|s| s.name == name) {
+            anyhow::bail!("来源 {} 不存在", name);
+        }
+
+        // 同时移除该来源的模板索引和本地代码
+        sources.retain(|s| s.name != name);
+        index.sources = Some(sources);
+        index.templates.retain(|t| t.source.as_deref() != Some(name));
+        index.save(Path::new(&config_file))?;
+
+        let codes_dir = format!("{}/{}/{}/{}", self.home_dir, config::SCAFFOLD_DIR_NAME, config::CODES_DIR_NAME, name);
+        if Path::new(&codes_dir).exists() {
+            remove(&codes_dir).with_context(|| format!("删除目录 {} 失败", codes_dir))?;
+        }
+
+        info!(name = %name, "已删除模板来源");
+        println!("已删除来源 {}", name.cyan());
+        Ok(())
+    }
+
+    pub fn list(&self) -> Result<()> {
+        let index = TemplateIndex::load(Path::new(&self.config_file()))?;
+        let sources = index.sources();
+        if sources.is_empty() {
+            println!("未配置任何模板来源，使用 {} 添加", "scaffold source add <名称> <URL>".cyan());
+            return Ok(());
+        }
+
+        let width = sources.iter().map(|s| s.name.len()).max().unwrap_or_default();
+        for source in &sources {
+            let templates: Vec<&TemplateEntry> = index
+                .templates
+                .iter()
+                .filter(|t| t.source.as_ref() == Some(&source.name))
+                .collect();
+            let (url, reference) = source.remote();
+            let reference = reference.map(|r| format!("#{}", r)).unwrap_or_default();
+            // 同一来源的模板来自同一次检出，取第一个模板记录的提交
+            let commit = templates
+                .iter()
+                .find_map(|t| t.commit.as_deref())
+                .map(|c| format!(" @ {}", short_commit(c)))
+                .unwrap_or_default();
+            println!(
+                "{:width$}  {}{}  {}",
+                source.name.cyan(),
+                url,
+                reference.yellow(),
+                format!("{} 个模板{}", templates.len(), commit).dimmed(),
+                width = width
+            );
+        }
+        Ok(())
+    }
+
+    fn config_file(&self) -> String {
+        format!("{}/{}", self.home_dir, config::HOME_SCAFFOLD_CONFIG)
+    }
+}
+
+impl Default for SourceHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
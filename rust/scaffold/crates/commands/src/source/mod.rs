@@ -0,0 +1,19 @@
+pub mod handler;
+
+use anyhow::Result;
+use handler::SourceHandler;
+
+/// 添加模板来源，`url` 可以写成 `url#ref`
+pub fn add(name: String, url: String, reference: Option<String>) -> Result<()> {
+    SourceHandler::new().add(&name, &url, reference.as_deref())
+}
+
+/// 删除模板来源
+pub fn remove(name: String) -> Result<()> {
+    SourceHandler::new().remove(&name)
+}
+
+/// 列出模板来源
+pub fn list() -> Result<()> {
+    SourceHandler::new().list()
+}
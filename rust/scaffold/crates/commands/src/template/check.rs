@@ -0,0 +1,657 @@
+//! 模板校验
+//!
+//! 发布模板前检查：scaffold.json 是否符合 JSON Schema，模板文件和路径能否被 Handlebars 编译，
+//! 引用的变量是否已声明，以及是否存在在任何变量取值下都不会生成的条件文件
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{Result, Context};
+use handlebars::template::{HelperTemplate, Parameter, Template, TemplateElement};
+use model::variables::{check_variables, RESERVED_NAMES};
+use model::{manifest_schema, TemplateManifest, TemplateVariable, VariableKind};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use utils::debug;
+use walkdir::WalkDir;
+use crate::conditions::PathFilter;
+use crate::config;
+use crate::new::render::{condition_template, text_content, TemplateRenderer, HELPERS};
+use super::snapshot::is_fixture_path;
+
+/// 可达性检查最多尝试的变量取值组合数，超过时跳过该检查
+const MAX_COMBINATIONS: usize = 256;
+
+/// multiselect 的可选项不超过该数量时枚举全部子集，否则只尝试空集、单选和全选
+const MAX_SUBSET_OPTIONS: usize = 4;
+
+/// 问题级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// 模板无法正常生成
+    Error,
+    /// 模板可以生成，但可能不符合作者预期
+    Warning,
+}
+
+/// 校验发现的一个问题
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    /// 相对模板目录的文件（可带行列号），或 `scaffold.json#/JSON 指针`
+    pub location: String,
+    pub message: String,
+}
+
+impl Issue {
+    fn error(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Error, location: location.into(), message: message.into() }
+    }
+
+    fn warning(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Warning, location: location.into(), message: message.into() }
+    }
+}
+
+/// 校验模板目录，返回发现的问题
+///
+/// scaffold.json 本身无效时只报告清单的问题，不再检查模板文件
+pub fn check_template(dir: &Path) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+    let Some(manifest) = check_manifest(dir, &mut issues)? else {
+        return Ok(issues);
+    };
+
+    let entries = template_entries(dir)?;
+    let declared: HashSet<&str> = RESERVED_NAMES
+        .iter()
+        .copied()
+        .chain(manifest.variables.iter().map(|v| v.name.as_str()))
+        .collect();
+
+    check_manifest_templates(&manifest, &declared, &mut issues);
+    check_files(dir, &entries, &declared, &mut issues)?;
+    check_conditions(&manifest, &entries, &mut issues);
+    Ok(issues)
+}
+
+/// 模板目录中的一个条目
+struct Entry {
+    /// 相对模板目录的路径
+    relative: PathBuf,
+    is_dir: bool,
+}
+
+/// 按生成顺序列出模板目录中的文件和目录，跳过 `.git` 和测试用例
+fn template_entries(dir: &Path) -> Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    let walker = WalkDir::new(dir)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            let relative = e.path().strip_prefix(dir).unwrap_or(e.path());
+            e.file_name() != ".git" && !is_fixture_path(dir, relative)
+        });
+    for entry in walker {
+        let entry = entry.with_context(|| format!("遍历模板目录 {} 失败", dir.display()))?;
+        let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path()).to_path_buf();
+        entries.push(Entry { relative, is_dir: entry.file_type().is_dir() });
+    }
+    Ok(entries)
+}
+
+/// 检查 scaffold.json，清单可以使用时返回解析结果
+fn check_manifest(dir: &Path, issues: &mut Vec<Issue>) -> Result<Option<TemplateManifest>> {
+    let file = config::SCAFFOLD_CONFIG_FILE;
+    let path = dir.join(file);
+    if !path.is_file() {
+        anyhow::bail!("{} 不是模板目录：缺少 {}", dir.display(), file);
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("读取文件 {} 失败", path.display()))?;
+
+    let value: Value = match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(e) => {
+            let position = format!(" at line {} column {}", e.line(), e.column());
+            let message = e.to_string();
+            let message = message.strip_suffix(&position).unwrap_or(&message);
+            issues.push(Issue::error(format!("{}:{}:{}", file, e.line(), e.column()), message));
+            return Ok(None);
+        }
+    };
+
+    let validator = jsonschema::validator_for(&manifest_schema())
+        .map_err(|e| anyhow::anyhow!("编译 {} 的 JSON Schema 失败: {}", file, e))?;
+    let before = issues.len();
+    for error in validator.iter_errors(&value) {
+        issues.push(Issue::error(format!("{}#{}", file, error.instance_path()), error.to_string()));
+    }
+    if issues.len() > before {
+        return Ok(None);
+    }
+
+    let manifest: TemplateManifest = match serde_json::from_value(value) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            issues.push(Issue::error(file, e.to_string()));
+            return Ok(None);
+        }
+    };
+    if let Err(e) = manifest.check_version() {
+        issues.push(Issue::error(format!("{}#/schema_version", file), e.to_string()));
+        return Ok(None);
+    }
+    if let Err(e) = check_variables(&manifest.variables) {
+        issues.push(Issue::error(format!("{}#/variables", file), format!("{:#}", e)));
+    }
+    Ok(Some(manifest))
+}
+
+/// 检查 scaffold.json 中的 Handlebars 表达式：变量默认值、条件和钩子命令
+///
+/// 变量默认值在询问该变量时渲染，只能引用在它之前声明的变量
+fn check_manifest_templates(manifest: &TemplateManifest, declared: &HashSet<&str>, issues: &mut Vec<Issue>) {
+    let file = config::SCAFFOLD_CONFIG_FILE;
+    for (index, variable) in manifest.variables.iter().enumerate() {
+        if let VariableKind::String { default: Some(default) } = &variable.kind {
+            let earlier: HashSet<&str> = RESERVED_NAMES
+                .iter()
+                .copied()
+                .chain(manifest.variables[..index].iter().map(|v| v.name.as_str()))
+                .collect();
+            let location = format!("{}#/variables/{}/default", file, index);
+            check_source(default, &location, false, &earlier, declared, issues);
+        }
+    }
+
+    for (index, rule) in manifest.conditions.iter().enumerate() {
+        let location = format!("{}#/conditions/{}/when", file, index);
+        check_source(&condition_template(&rule.when), &location, false, declared, declared, issues);
+    }
+
+    for (stage, commands) in [("pre_generate", &manifest.hooks.pre_generate), ("post_generate", &manifest.hooks.post_generate)] {
+        for (index, command) in commands.iter().enumerate() {
+            let location = format!("{}#/hooks/{}/{}", file, stage, index);
+            if command.contains("{{") {
+                issues.push(Issue::warning(location, "钩子命令按原样执行，不会渲染 Handlebars 变量，请改用 $SCAFFOLD_<变量名> 环境变量"));
+            }
+        }
+    }
+}
+
+/// 编译模板文件的内容和路径，并检查其中引用的变量
+fn check_files(dir: &Path, entries: &[Entry], declared: &HashSet<&str>, issues: &mut Vec<Issue>) -> Result<()> {
+    for entry in entries {
+        let location = entry.relative.to_string_lossy().replace('\\', "/");
+        // 上级目录的名称在遍历到该目录时已检查
+        if let Some(name) = entry.relative.file_name().and_then(|n| n.to_str()).filter(|n| n.contains("{{")) {
+            check_source(name, &location, false, declared, declared, issues);
+        }
+
+        // scaffold.json 中的表达式已单独检查
+        if entry.is_dir || entry.relative == Path::new(config::SCAFFOLD_CONFIG_FILE) {
+            continue;
+        }
+        let path = dir.join(&entry.relative);
+        let bytes = fs::read(&path).with_context(|| format!("读取文件 {} 失败", path.display()))?;
+        if let Some(text) = text_content(&bytes).filter(|t| t.contains("{{")) {
+            check_source(text, &location, true, declared, declared, issues);
+        }
+    }
+    Ok(())
+}
+
+/// 编译一段 Handlebars 源码并检查其中的引用
+///
+/// `visible` 是该位置可以引用的变量，`declared` 用于区分“尚未声明”和“声明得太晚”
+fn check_source(
+    source: &str,
+    location: &str,
+    with_position: bool,
+    visible: &HashSet<&str>,
+    declared: &HashSet<&str>,
+    issues: &mut Vec<Issue>,
+) {
+    let at = |position: Option<(usize, usize)>| match position {
+        Some((line, column)) if with_position => format!("{}:{}:{}", location, line, column),
+        _ => location.to_string(),
+    };
+
+    let template = match Template::compile(source) {
+        Ok(template) => template,
+        Err(e) => {
+            issues.push(Issue::error(at(e.pos()), format!("Handlebars 语法错误: {}", e.reason())));
+            return;
+        }
+    };
+
+    for reference in references(&template) {
+        let message = match &reference.kind {
+            ReferenceKind::Variable(name) if visible.contains(name.as_str()) => continue,
+            ReferenceKind::Variable(name) if declared.contains(name.as_str()) => {
+                format!("变量 {} 在此处尚未询问，只能引用在它之前声明的变量", name)
+            }
+            ReferenceKind::Variable(name) => format!("引用了未声明的变量 {}", name),
+            ReferenceKind::Helper(name) => format!("未知的 helper {}", name),
+            ReferenceKind::Partial => "不支持 partial 和 decorator".to_string(),
+        };
+        issues.push(Issue::error(at(reference.position), message));
+    }
+}
+
+/// Handlebars 模板中的一处引用
+struct Reference {
+    /// 行号和列号，从 1 开始
+    position: Option<(usize, usize)>,
+    kind: ReferenceKind,
+}
+
+enum ReferenceKind {
+    /// 顶层变量名
+    Variable(String),
+    /// 调用的 helper
+    Helper(String),
+    /// partial 或 decorator
+    Partial,
+}
+
+/// 收集模板引用的顶层变量和 helper
+///
+/// `each`、`with` 和带块参数的块内上下文会变化，其中只检查以 `@root` 开头的路径
+fn references(template: &Template) -> Vec<Reference> {
+    let mut references = Vec::new();
+    collect_template(template, false, &mut references);
+    references
+}
+
+fn collect_template(template: &Template, scoped: bool, references: &mut Vec<Reference>) {
+    for (index, element) in template.elements.iter().enumerate() {
+        let position = template.mapping.get(index).map(|m| (m.0, m.1));
+        match element {
+            TemplateElement::Expression(helper) | TemplateElement::HtmlExpression(helper) => {
+                collect_expression(helper, scoped, position, references);
+            }
+            TemplateElement::HelperBlock(helper) => {
+                collect_expression(helper, scoped, position, references);
+                let inner = scoped
+                    || helper.block_param.is_some()
+                    || matches!(helper.name.as_name(), Some("each" | "with"));
+                if let Some(body) = &helper.template {
+                    collect_template(body, inner, references);
+                }
+                if let Some(inverse) = &helper.inverse {
+                    collect_template(inverse, scoped, references);
+                }
+            }
+            TemplateElement::DecoratorExpression(_)
+            | TemplateElement::DecoratorBlock(_)
+            | TemplateElement::PartialExpression(_)
+            | TemplateElement::PartialBlock(_) => {
+                references.push(Reference { position, kind: ReferenceKind::Partial });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `{{name}}` 可能是变量也可能是无参数的 helper；带参数或块的表达式中 name 是 helper
+fn collect_expression(
+    helper: &HelperTemplate,
+    scoped: bool,
+    position: Option<(usize, usize)>,
+    references: &mut Vec<Reference>,
+) {
+    let name_only = !helper.block && helper.params.is_empty() && helper.hash.is_empty();
+    match helper.name.as_name() {
+        Some(name) if HELPERS.contains(&name) => {}
+        Some(name) if name_only => collect_path(name, scoped, position, references),
+        Some(name) => references.push(Reference { position, kind: ReferenceKind::Helper(name.to_string()) }),
+        None => collect_parameter(&helper.name, scoped, position, references),
+    }
+
+    for parameter in helper.params.iter().chain(helper.hash.values()) {
+        collect_parameter(parameter, scoped, position, references);
+    }
+}
+
+fn collect_parameter(
+    parameter: &Parameter,
+    scoped: bool,
+    position: Option<(usize, usize)>,
+    references: &mut Vec<Reference>,
+) {
+    match parameter {
+        Parameter::Name(name) => collect_path(name, scoped, position, references),
+        Parameter::Path(_) => {
+            if let Some(raw) = parameter.as_name() {
+                collect_path(raw, scoped, position, references);
+            }
+        }
+        Parameter::Subexpression(subexpression) => {
+            if let TemplateElement::Expression(helper) = subexpression.as_element() {
+                collect_expression(helper, scoped, position, references);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 记录路径的顶层变量名，`this`、`../` 和 `@index` 等相对当前上下文的路径不检查
+fn collect_path(raw: &str, scoped: bool, position: Option<(usize, usize)>, references: &mut Vec<Reference>) {
+    let path = match raw.strip_prefix("@root.").or_else(|| raw.strip_prefix("@root/")) {
+        Some(rest) => rest,
+        None if scoped => return,
+        None => raw,
+    };
+    if path.is_empty() || path.starts_with(['.', '@', '[']) || path == "this" || path.starts_with("this.") || path.starts_with("this/") {
+        return;
+    }
+    let name = path.split(['.', '/', '[']).next().unwrap_or(path);
+    references.push(Reference { position, kind: ReferenceKind::Variable(name.to_string()) });
+}
+
+/// 检查条件规则：没有匹配任何文件的规则，以及在任何变量取值下都不会生成的文件和目录
+///
+/// 只枚举 bool、select、multiselect 变量的取值；条件引用了其他变量的路径无法判断，视为可以生成
+fn check_conditions(manifest: &TemplateManifest, entries: &[Entry], issues: &mut Vec<Issue>) {
+    if manifest.conditions.is_empty() {
+        return;
+    }
+    let file = config::SCAFFOLD_CONFIG_FILE;
+    // 条件表达式或 glob 无效时已在前面报告
+    let Ok(filter) = PathFilter::new(&manifest.conditions, |_| Ok(true)) else {
+        return;
+    };
+
+    let mut matched = HashSet::new();
+    for entry in entries {
+        matched.extend(filter.rules_for(&entry.relative, entry.is_dir).into_iter().map(|r| r.path.as_str()));
+    }
+    for (index, rule) in manifest.conditions.iter().enumerate() {
+        if !matched.contains(rule.path.as_str()) {
+            issues.push(Issue::warning(
+                format!("{}#/conditions/{}/path", file, index),
+                format!("规则 {} 没有匹配任何文件", rule.path),
+            ));
+        }
+    }
+
+    // 只引用可枚举变量的条件表达式
+    let enumerable = |name: &str| {
+        name == "template"
+            || manifest.variables.iter().any(|v| v.name == name && candidate_values(v).is_some())
+    };
+    let decidable: HashSet<&str> = manifest
+        .conditions
+        .iter()
+        .filter(|rule| {
+            Template::compile(&condition_template(&rule.when)).is_ok_and(|t| {
+                references(&t).iter().all(|r| matches!(&r.kind, ReferenceKind::Variable(name) if enumerable(name)))
+            })
+        })
+        .map(|rule| rule.when.as_str())
+        .collect();
+
+    let Some(combinations) = combinations(manifest) else {
+        debug!(template = %manifest.name, "条件变量的取值组合过多，跳过可达性检查");
+        return;
+    };
+
+    // 对每种取值组合按生成时的规则遍历，记录至少生成过一次的路径；无法判断的条件视为成立
+    let mut reachable = vec![false; entries.len()];
+    for context in combinations {
+        let renderer = TemplateRenderer::new(context);
+        let eval = |expr: &str| if decidable.contains(expr) { renderer.eval_condition(expr) } else { Ok(true) };
+        let Ok(filter) = PathFilter::new(&manifest.conditions, eval) else {
+            return;
+        };
+        let mut skipped: Vec<&Path> = Vec::new();
+        for (index, entry) in entries.iter().enumerate() {
+            if skipped.iter().any(|dir| entry.relative.starts_with(dir)) {
+                continue;
+            }
+            if filter.excluded_by(&entry.relative, entry.is_dir).is_some() {
+                skipped.push(&entry.relative);
+            } else {
+                reachable[index] = true;
+            }
+        }
+    }
+
+    // 只报告最外层的不可达路径
+    let mut reported: Vec<&Path> = Vec::new();
+    for (entry, reachable) in entries.iter().zip(reachable) {
+        if reachable || reported.iter().any(|dir| entry.relative.starts_with(dir)) {
+            continue;
+        }
+        let rules = filter.rules_for(&entry.relative, entry.is_dir);
+        if rules.is_empty() {
+            // 由上级目录的规则决定
+            continue;
+        }
+        let conditions: Vec<&str> = rules.iter().map(|r| r.when.as_str()).collect();
+        issues.push(Issue::warning(
+            entry.relative.to_string_lossy().replace('\\', "/"),
+            format!("在任何变量取值下都不会生成：条件 {} 不能成立", conditions.join("、")),
+        ));
+        reported.push(&entry.relative);
+    }
+}
+
+/// 枚举条件求值用的渲染上下文，组合数超过 [`MAX_COMBINATIONS`] 时返回 None
+///
+/// 无法枚举的变量取默认值
+fn combinations(manifest: &TemplateManifest) -> Option<Vec<Value>> {
+    let mut base = Map::new();
+    base.insert("project_name".to_string(), Value::String(manifest.name.clone()));
+    base.insert("template".to_string(), serde_json::to_value(manifest).ok()?);
+    for variable in &manifest.variables {
+        let default = variable.kind.declared_default().unwrap_or(Value::Null);
+        base.insert(variable.name.clone(), default);
+    }
+
+    let mut contexts = vec![base];
+    for variable in &manifest.variables {
+        let Some(values) = candidate_values(variable) else {
+            continue;
+        };
+        if contexts.len() * values.len() > MAX_COMBINATIONS {
+            return None;
+        }
+        contexts = contexts
+            .into_iter()
+            .flat_map(|context| {
+                values.iter().map(move |value| {
+                    let mut context = context.clone();
+                    context.insert(variable.name.clone(), value.clone());
+                    context
+                })
+            })
+            .collect();
+    }
+    Some(contexts.into_iter().map(Value::Object).collect())
+}
+
+/// 变量所有可能的取值，string 和 number 无法枚举时返回 None
+fn candidate_values(variable: &TemplateVariable) -> Option<Vec<Value>> {
+    let strings = |items: &[&String]| Value::Array(items.iter().map(|s| Value::String(s.to_string())).collect());
+    match &variable.kind {
+        VariableKind::Bool { .. } => Some(vec![Value::Bool(true), Value::Bool(false)]),
+        VariableKind::Select { options, .. } => Some(options.iter().cloned().map(Value::String).collect()),
+        VariableKind::Multiselect { options, .. } if options.len() <= MAX_SUBSET_OPTIONS => Some(
+            (0..1usize << options.len())
+                .map(|mask| {
+                    let subset: Vec<&String> =
+                        options.iter().enumerate().filter(|(i, _)| mask & (1 << i) != 0).map(|(_, o)| o).collect();
+                    strings(&subset)
+                })
+                .collect(),
+        ),
+        VariableKind::Multiselect { options, .. } => {
+            let all: Vec<&String> = options.iter().collect();
+            Some(
+                std::iter::once(strings(&[]))
+                    .chain(options.iter().map(|o| strings(&[o])))
+                    .chain(std::iter::once(strings(&all)))
+                    .collect(),
+            )
+        }
+        VariableKind::String { .. } | VariableKind::Number { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// 在临时目录中创建模板
+    fn template(manifest: Value, files: &[(&str, &str)]) -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(config::SCAFFOLD_CONFIG_FILE), manifest.to_string()).unwrap();
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn issues(manifest: Value, files: &[(&str, &str)]) -> Vec<(Severity, String, String)> {
+        let dir = template(manifest, files);
+        check_template(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|i| (i.severity, i.location, i.message))
+            .collect()
+    }
+
+    #[test]
+    fn valid_template_has_no_issues() {
+        let manifest = json!({
+            "name": "demo",
+            "variables": [{ "name": "crate_name", "type": "string", "default": "{{snakeCase project_name}}" }]
+        });
+        assert!(issues(manifest, &[("src/{{crate_name}}.rs", "// {{project_name}} {{template.name}}\n")]).is_empty());
+    }
+
+    #[test]
+    fn undeclared_variables_are_reported() {
+        let found = issues(
+            json!({ "name": "demo" }),
+            &[("src/main.rs", "fn main() {}\n// {{crate_nam}}\n"), ("{{missing}}.txt", "")],
+        );
+        assert_eq!(found.len(), 2, "{:?}", found);
+        assert!(found.iter().all(|(severity, ..)| *severity == Severity::Error));
+        assert!(found.iter().any(|(_, location, message)| {
+            location == "src/main.rs:2:4" && message == "引用了未声明的变量 crate_nam"
+        }), "{:?}", found);
+        assert!(found.iter().any(|(_, location, message)| {
+            location == "{{missing}}.txt" && message == "引用了未声明的变量 missing"
+        }), "{:?}", found);
+    }
+
+    #[test]
+    fn scoped_paths_are_not_flagged() {
+        let manifest = json!({
+            "name": "demo",
+            "variables": [{ "name": "features", "type": "multiselect", "options": ["a", "b"] }]
+        });
+        let content = concat!(
+            "{{#each features as |feature index|}}{{feature}} {{index}} {{@index}}{{/each}}\n",
+            "{{#each features}}{{this}} {{@root.project_name}} {{../project_name}}{{/each}}\n",
+            "{{#with template}}{{name}} {{description}}{{/with}}\n",
+            "{{#if (contains features \"a\")}}a{{/if}}\n",
+        );
+        assert_eq!(issues(manifest.clone(), &[("README.md", content)]), []);
+
+        // 块参数离开作用域后不再可用
+        let outside = "{{#each features as |feature|}}{{feature}}{{/each}}{{feature}}\n";
+        let found = issues(manifest, &[("README.md", outside)]);
+        assert_eq!(found.len(), 1, "{:?}", found);
+        assert_eq!(found[0].2, "引用了未声明的变量 feature");
+    }
+
+    #[test]
+    fn default_cannot_reference_later_variable() {
+        let manifest = json!({
+            "name": "demo",
+            "variables": [
+                { "name": "crate_name", "type": "string", "default": "{{package}}" },
+                { "name": "package", "type": "string" }
+            ]
+        });
+        let found = issues(manifest, &[]);
+        assert_eq!(found.len(), 1, "{:?}", found);
+        assert_eq!(found[0].0, Severity::Error);
+        assert!(found[0].1.starts_with("scaffold.json#/variables/0/default"), "{:?}", found);
+        assert!(found[0].2.contains("尚未询问"), "{:?}", found);
+    }
+
+    #[test]
+    fn unreachable_condition_reports_outermost_path() {
+        let manifest = json!({
+            "name": "demo",
+            "variables": [{ "name": "license", "type": "select", "options": ["MIT", "Apache-2.0"] }],
+            "conditions": [
+                { "path": "gpl/**", "when": "(eq license \"GPL\")" },
+                { "path": "LICENSE-MIT", "when": "(eq license \"MIT\")" }
+            ]
+        });
+        let found = issues(manifest, &[("gpl/LICENSE", "GPL"), ("gpl/NOTICE", ""), ("LICENSE-MIT", "MIT")]);
+        assert_eq!(found.len(), 1, "{:?}", found);
+        assert_eq!(found[0].0, Severity::Warning);
+        assert_eq!(found[0].1, "gpl");
+        assert!(found[0].2.contains("不会生成"), "{:?}", found);
+    }
+
+    #[test]
+    fn rule_matching_no_files_is_reported() {
+        let manifest = json!({
+            "name": "demo",
+            "variables": [{ "name": "use_docker", "type": "bool" }],
+            "conditions": [{ "path": "docker/**", "when": "use_docker" }]
+        });
+        let found = issues(manifest, &[("README.md", "")]);
+        assert_eq!(
+            found,
+            [(
+                Severity::Warning,
+                "scaffold.json#/conditions/0/path".to_string(),
+                "规则 docker/** 没有匹配任何文件".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn unknown_manifest_keys_are_errors() {
+        let found = issues(json!({ "name": "demo", "varibles": [] }), &[]);
+        assert_eq!(found.len(), 1, "{:?}", found);
+        assert_eq!(found[0].1, "scaffold.json#");
+        assert!(found[0].2.contains("varibles"), "{:?}", found);
+    }
+
+    #[test]
+    fn hooks_are_not_rendered() {
+        let manifest = json!({
+            "name": "demo",
+            "hooks": { "post_generate": ["echo \"$SCAFFOLD_PROJECT_NAME\"", "echo {{project_name}}"] }
+        });
+        let found = issues(manifest, &[]);
+        assert_eq!(found.len(), 1, "{:?}", found);
+        assert_eq!(found[0].0, Severity::Warning);
+        assert_eq!(found[0].1, "scaffold.json#/hooks/post_generate/1");
+    }
+
+    #[test]
+    fn target_dir_is_reserved() {
+        let found = issues(json!({ "name": "demo", "variables": [{ "name": "target_dir", "type": "string" }] }), &[]);
+        assert_eq!(found.len(), 1, "{:?}", found);
+        assert_eq!(found[0].0, Severity::Error);
+        assert!(found[0].2.contains("SCAFFOLD_TARGET_DIR"), "{:?}", found);
+    }
+}
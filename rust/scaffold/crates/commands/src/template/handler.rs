@@ -0,0 +1,279 @@
+use std::env;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
+use anyhow::{Result, Context};
+use colored::{Color, Colorize};
+use dialoguer::{theme::ColorfulTheme, Input, MultiSelect};
+use model::{manifest_schema, TemplateManifest};
+use utils::warn;
+use crate::config::SCAFFOLD_CONFIG_FILE;
+use crate::new::conflict::print_labeled_diff;
+use crate::new::handler::{read_local_config, NewHandler};
+use crate::new::NewOptions;
+use crate::output::{OutputFormat, Table};
+use super::check::{check_template, Issue, Severity};
+use super::init::{Candidate, Project};
+use super::snapshot::{self, Difference, Fixture};
+
+pub struct TemplateHandler {
+    current_dir: String,
+}
+
+impl TemplateHandler {
+    pub fn new() -> Self {
+        let current_dir = env::current_dir()
+            .unwrap_or_else(|_| Path::new(".").to_path_buf())
+            .to_string_lossy()
+            .to_string();
+        Self { current_dir }
+    }
+
+    /// 打印 scaffold.json 的 JSON Schema
+    pub fn schema(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(&manifest_schema()).with_context(|| "序列化 JSON Schema 失败")?;
+        println!("{}", json);
+        Ok(())
+    }
+
+    /// 校验模板目录，存在错误时返回 Err，只有警告时仍然通过
+    pub fn validate(&self, dir: &Path, format: OutputFormat) -> Result<()> {
+        let dir = Path::new(&self.current_dir).join(dir);
+        let issues = check_template(&dir)?;
+        let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+
+        match format {
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(&issues).with_context(|| "序列化校验结果失败")?;
+                println!("{}", json);
+            }
+            OutputFormat::Text if issues.is_empty() => {
+                println!("{} 模板 {} 校验通过", "✔".green(), dir.display());
+            }
+            OutputFormat::Text => print_issues(&issues),
+        }
+
+        if errors > 0 {
+            anyhow::bail!("模板 {} 校验未通过：{} 个错误", dir.display(), errors);
+        }
+        Ok(())
+    }
+
+    /// 运行模板的快照测试
+    ///
+    /// 每个用例在临时目录中按 `new` 的完整流程生成项目，不执行钩子，再与快照比较；
+    /// `bless` 为 true 时用生成结果替换快照
+    pub fn test(&self, dir: &Path, bless: bool) -> Result<()> {
+        let dir = Path::new(&self.current_dir).join(dir);
+        let template = read_local_config(&dir)?;
+        let dir = Path::new(&template.path);
+        let fixtures = snapshot::fixtures(dir)?;
+        if fixtures.is_empty() {
+            anyhow::bail!("模板 {} 没有测试用例，请在 tests/ 下添加 <用例名>.json 答案文件", template.manifest.name);
+        }
+
+        let mut failed = Vec::new();
+        for fixture in &fixtures {
+            match self.run_fixture(dir, fixture, bless) {
+                Ok(true) => {}
+                Ok(false) => failed.push(fixture.name.as_str()),
+                Err(e) => {
+                    println!("{} {}: {:#}", "✘".red(), fixture.name, e);
+                    failed.push(fixture.name.as_str());
+                }
+            }
+        }
+
+        println!();
+        let summary = format!("{} 个用例，{} 个失败", fixtures.len(), failed.len());
+        if failed.is_empty() {
+            println!("{}", summary.green());
+            Ok(())
+        } else {
+            println!("{}", summary.red().bold());
+            anyhow::bail!("模板 {} 的快照测试失败：{}", template.manifest.name, failed.join(", "))
+        }
+    }
+
+    /// 运行一个用例，返回是否通过
+    fn run_fixture(&self, dir: &Path, fixture: &Fixture, bless: bool) -> Result<bool> {
+        let temp = tempfile::tempdir().with_context(|| "创建临时目录失败")?;
+        let options = NewOptions {
+            answers: Some(fixture.answers.clone()),
+            yes: true,
+            no_hooks: true,
+            no_input: true,
+            ..Default::default()
+        };
+        // 使用空的主目录，结果不受本机模板索引和信任记录影响
+        let home_dir = temp.path().join("home");
+        let work_dir = temp.path().join("work");
+        fs::create_dir_all(&work_dir).with_context(|| format!("创建目录 {} 失败", work_dir.display()))?;
+        NewHandler::new(&home_dir, &work_dir).execute(Some(dir.display().to_string()), None, options)?;
+        let output = fs::read_dir(&work_dir)
+            .with_context(|| format!("读取目录 {} 失败", work_dir.display()))?
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("没有生成项目"))?
+            .with_context(|| format!("读取目录 {} 失败", work_dir.display()))?
+            .path();
+
+        if bless {
+            snapshot::bless(&output, &fixture.snapshot)?;
+            println!("{} {} 已更新快照", "✔".green(), fixture.name);
+            return Ok(true);
+        }
+        if !fixture.snapshot.is_dir() {
+            println!("{} {}: 缺少快照 {}，使用 --bless 生成", "✘".red(), fixture.name, fixture.snapshot.display());
+            return Ok(false);
+        }
+
+        let differences = snapshot::compare(&output, &fixture.snapshot)?;
+        if differences.is_empty() {
+            println!("{} {}", "✔".green(), fixture.name);
+            return Ok(true);
+        }
+        println!("{} {}: {} 个文件与快照不同", "✘".red(), fixture.name, differences.len());
+        for difference in &differences {
+            match difference {
+                Difference::Added(path) => println!("  {} {}（快照中没有）", "+".green(), path.display()),
+                Difference::Removed(path) => println!("  {} {}（没有生成）", "-".red(), path.display()),
+                Difference::Changed { path, expected, actual } => {
+                    print_labeled_diff(path, ("快照", expected), ("生成", actual))
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// 由已有项目生成模板：询问元数据，把项目复制到 `output` 并替换候选字符串，写入 scaffold.json 并校验
+    ///
+    /// 项目目录本身不会被修改。`output` 缺省为项目旁边的 `<目录名>-template`，必须不存在；
+    /// `yes` 为 true 时不询问，全部采用检测到的默认值
+    pub fn init(&self, dir: &Path, output: Option<&Path>, yes: bool) -> Result<()> {
+        let root = Path::new(&self.current_dir).join(dir);
+        let root = root.canonicalize().with_context(|| format!("目录 {} 不存在", root.display()))?;
+        if root.join(SCAFFOLD_CONFIG_FILE).exists() {
+            anyhow::bail!("{} 已存在，目录已经是模板", root.join(SCAFFOLD_CONFIG_FILE).display());
+        }
+        let dir_name = root.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let output = match output {
+            Some(output) => Path::new(&self.current_dir).join(output),
+            None => root.with_file_name(format!("{}-template", dir_name)),
+        };
+        if output.exists() {
+            anyhow::bail!("输出目录 {} 已存在，请使用 --output 指定新目录", output.display());
+        }
+        if output.starts_with(&root) {
+            anyhow::bail!("输出目录 {} 不能位于项目目录 {} 中", output.display(), root.display());
+        }
+        if !yes && !std::io::stdin().is_terminal() {
+            anyhow::bail!("当前不是交互式终端，请使用 --yes 采用检测到的默认值");
+        }
+
+        let project = Project::scan(&root)?;
+        let build_dirs = project.build_dirs();
+        if !build_dirs.is_empty() {
+            warn!(dirs = %build_dirs.join(", "), "目录中存在构建产物，请在提交模板前删除");
+        }
+
+        let theme = ColorfulTheme::default();
+        let ask = |prompt: &str, default: Option<String>| -> Result<Option<String>> {
+            if yes {
+                return Ok(default);
+            }
+            let mut input = Input::<String>::with_theme(&theme).with_prompt(prompt).allow_empty(true);
+            if let Some(default) = default {
+                input = input.default(default);
+            }
+            let value = input.interact_text().with_context(|| format!("读取{}失败", prompt))?;
+            Ok(Some(value.trim().to_string()).filter(|v| !v.is_empty()))
+        };
+
+        let mut manifest = TemplateManifest::new(&ask("模板名称", Some(dir_name))?.unwrap_or_default());
+        if manifest.name.is_empty() {
+            anyhow::bail!("模板名称不能为空");
+        }
+        manifest.description = ask("模板描述", None)?;
+        manifest.language = ask("语言", project.language.clone())?;
+        manifest.tags = ask("标签（逗号分隔）", None)?.map(|tags| {
+            tags.split(',').map(str::trim).filter(|t| !t.is_empty()).map(String::from).collect()
+        });
+        manifest.author = project.author.clone();
+
+        let mut candidates = project.candidates()?;
+        if !yes && !candidates.is_empty() {
+            let items: Vec<String> = candidates.iter().map(describe_candidate).collect();
+            let defaults = vec![true; items.len()];
+            let selected = MultiSelect::with_theme(&theme)
+                .with_prompt("选择要替换为变量的字符串（空格切换，回车确认）")
+                .items(&items)
+                .defaults(&defaults)
+                .interact()
+                .with_context(|| "选择候选字符串失败")?;
+            candidates = selected.into_iter().map(|i| candidates[i].clone()).collect();
+        }
+
+        let changes = project.apply(&candidates, &output)?;
+        let manifest_path = output.join(SCAFFOLD_CONFIG_FILE);
+        manifest.variables = candidates.iter().filter_map(|c| c.variable.clone()).collect();
+        let json = serde_json::to_string_pretty(&manifest).with_context(|| "序列化 scaffold.json 失败")?;
+        fs::write(&manifest_path, json + "\n")
+            .with_context(|| format!("写入 {} 失败", manifest_path.display()))?;
+
+        for candidate in &candidates {
+            println!("  {} → {}（{} 处）", candidate.text, candidate.expression.cyan(), candidate.count);
+        }
+        println!(
+            "{} 已在 {} 生成模板 {}：替换 {} 个文件的内容，重命名 {} 个路径",
+            "✔".green(),
+            output.display(),
+            manifest.name,
+            changes.files,
+            changes.renamed
+        );
+
+        let issues = check_template(&output)?;
+        if !issues.is_empty() {
+            println!();
+            print_issues(&issues);
+        }
+        let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+        if errors > 0 {
+            anyhow::bail!("生成的模板未通过校验：{} 个错误，请按提示修改", errors);
+        }
+        println!("\n可以使用 scaffold new -t {} 试用模板", output.display());
+        Ok(())
+    }
+}
+
+/// 候选字符串在选择列表中的显示文字
+fn describe_candidate(candidate: &Candidate) -> String {
+    format!("{} → {}（{}，{} 处）", candidate.text, candidate.expression, candidate.origin, candidate.count)
+}
+
+/// 以表格打印校验问题和汇总
+fn print_issues(issues: &[Issue]) {
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    let mut table = Table::new(&["级别", "位置", "问题"]).color(1, Color::Yellow);
+    for issue in issues {
+        let severity = match issue.severity {
+            Severity::Error => "错误",
+            Severity::Warning => "警告",
+        };
+        table.row(vec![severity.to_string(), issue.location.clone(), issue.message.clone()]);
+    }
+    table.print();
+    println!();
+    let summary = format!("{} 个错误，{} 个警告", errors, issues.len() - errors);
+    if errors > 0 {
+        println!("{}", summary.red().bold());
+    } else {
+        println!("{}", summary.yellow());
+    }
+}
+
+impl Default for TemplateHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,346 @@
+//! 由已有项目生成模板
+//!
+//! 在项目中查找项目名称、作者等字符串，把项目复制到新目录时替换为 Handlebars 表达式，
+//! 并把文件中原有的 `{{` 转义为 `\{{`，使生成的项目与原项目一致。原项目不会被修改
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{Result, Context};
+use model::{TemplateVariable, VariableKind};
+use regex::Regex;
+use serde_json::{json, Value};
+use walkdir::WalkDir;
+use crate::new::render::{text_content, TemplateRenderer};
+
+/// 扫描时跳过的目录：版本控制和构建产物
+pub const SKIPPED_DIRS: &[&str] = &[".git", "target", "node_modules", "dist", "build"];
+
+/// 候选字符串的最短长度，更短的字符串容易误替换
+const MIN_CANDIDATE_LEN: usize = 3;
+
+/// 由项目名称派生的写法，如 `my-app` 的 `my_app`、`MyApp`
+const NAME_VARIANTS: &[&str] = &[
+    "{{project_name}}",
+    "{{snakeCase project_name}}",
+    "{{kebabCase project_name}}",
+    "{{upperCamelCase project_name}}",
+    "{{lowerCamelCase project_name}}",
+    "{{shoutySnakeCase project_name}}",
+];
+
+/// 按标志文件识别项目语言，靠前的优先
+const LANGUAGE_MARKERS: &[(&str, &str)] = &[
+    ("Cargo.toml", "Rust"),
+    ("go.mod", "Go"),
+    ("tsconfig.json", "TypeScript"),
+    ("package.json", "JavaScript"),
+    ("pyproject.toml", "Python"),
+    ("requirements.txt", "Python"),
+    ("pom.xml", "Java"),
+    ("build.gradle.kts", "Kotlin"),
+    ("build.gradle", "Java"),
+    ("Gemfile", "Ruby"),
+    ("composer.json", "PHP"),
+    ("CMakeLists.txt", "C++"),
+];
+
+/// 待转换的项目
+pub struct Project {
+    pub root: PathBuf,
+    /// 相对项目目录的文件和目录，按遍历顺序
+    pub entries: Vec<(PathBuf, bool)>,
+    /// 识别出的语言
+    pub language: Option<String>,
+    /// 包管理文件中的包名
+    pub package_name: Option<String>,
+    /// 包管理文件中的作者
+    pub author: Option<String>,
+}
+
+/// 可以替换为表达式的字符串
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    /// 项目中的原始字符串
+    pub text: String,
+    /// 替换成的 Handlebars 表达式
+    pub expression: String,
+    /// 字符串的来源，如“目录名”
+    pub origin: String,
+    /// 在文件内容和文件名中出现的次数
+    pub count: usize,
+    /// 表达式引用的新变量
+    pub variable: Option<TemplateVariable>,
+}
+
+/// 转换结果
+pub struct Changes {
+    /// 内容被修改的文件数
+    pub files: usize,
+    /// 被重命名的文件和目录数
+    pub renamed: usize,
+}
+
+impl Project {
+    /// 扫描项目目录，跳过 [`SKIPPED_DIRS`]
+    pub fn scan(root: &Path) -> Result<Self> {
+        let mut entries = Vec::new();
+        let walker = WalkDir::new(root)
+            .min_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| !(e.file_type().is_dir() && SKIPPED_DIRS.iter().any(|d| e.file_name() == *d)));
+        for entry in walker {
+            let entry = entry.with_context(|| format!("遍历目录 {} 失败", root.display()))?;
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path()).to_path_buf();
+            entries.push((relative, entry.file_type().is_dir()));
+        }
+
+        let language = LANGUAGE_MARKERS
+            .iter()
+            .find(|(file, _)| root.join(file).is_file())
+            .map(|(_, language)| language.to_string());
+        let (package_name, author) = package_metadata(root);
+        Ok(Self { root: root.to_path_buf(), entries, language, package_name, author })
+    }
+
+    /// 项目中存在的构建产物目录，不会复制到模板中
+    pub fn build_dirs(&self) -> Vec<&str> {
+        SKIPPED_DIRS
+            .iter()
+            .copied()
+            .filter(|d| *d != ".git" && self.root.join(d).is_dir())
+            .collect()
+    }
+
+    /// 查找可以替换为表达式的字符串，只返回在项目中出现过的
+    ///
+    /// 目录名和包名及其派生写法替换为 `project_name` 的表达式，作者替换为新变量 `author`
+    pub fn candidates(&self) -> Result<Vec<Candidate>> {
+        let mut candidates: Vec<Candidate> = Vec::new();
+        let mut add = |text: String, expression: &str, origin: &str, variable: Option<TemplateVariable>| {
+            if text.len() >= MIN_CANDIDATE_LEN && !candidates.iter().any(|c| c.text == text) {
+                candidates.push(Candidate {
+                    text,
+                    expression: expression.to_string(),
+                    origin: origin.to_string(),
+                    count: 0,
+                    variable,
+                });
+            }
+        };
+
+        let dir_name = self.root.file_name().map(|n| n.to_string_lossy().to_string());
+        let names = [(dir_name, "目录名"), (self.package_name.clone(), "包名")];
+        for (name, origin) in names {
+            let Some(name) = name else { continue };
+            let renderer = TemplateRenderer::new(json!({ "project_name": name }));
+            for expression in NAME_VARIANTS {
+                let text = renderer.render_str(expression, expression)?;
+                let origin = if text == name { origin.to_string() } else { format!("{}的写法", origin) };
+                add(text, expression, &origin, None);
+            }
+        }
+
+        if let Some(author) = &self.author {
+            let variable = TemplateVariable {
+                name: "author".to_string(),
+                kind: VariableKind::String { default: Some(author.clone()) },
+                prompt: Some("作者".to_string()),
+                help: None,
+                validate: None,
+            };
+            add(author.clone(), "{{author}}", "作者", Some(variable));
+        }
+
+        let matcher = Matcher::new(&candidates)?;
+        for (relative, is_dir) in &self.entries {
+            if let Some(name) = relative.file_name().and_then(|n| n.to_str()) {
+                for (_, _, index) in matcher.find(name) {
+                    candidates[index].count += 1;
+                }
+            }
+            if *is_dir {
+                continue;
+            }
+            let path = self.root.join(relative);
+            let bytes = fs::read(&path).with_context(|| format!("读取文件 {} 失败", path.display()))?;
+            if let Some(text) = text_content(&bytes) {
+                for (_, _, index) in matcher.find(text) {
+                    candidates[index].count += 1;
+                }
+            }
+        }
+
+        candidates.retain(|c| c.count > 0);
+        Ok(candidates)
+    }
+
+    /// 把项目复制到 `output` 并替换选中的字符串，同时转义文本文件中原有的 `{{`
+    ///
+    /// 项目目录本身不会被修改，[`SKIPPED_DIRS`] 中的目录不会被复制
+    pub fn apply(&self, candidates: &[Candidate], output: &Path) -> Result<Changes> {
+        let matcher = Matcher::new(candidates)?;
+        let mut changes = Changes { files: 0, renamed: 0 };
+        fs::create_dir_all(output).with_context(|| format!("创建目录 {} 失败", output.display()))?;
+
+        for (relative, is_dir) in &self.entries {
+            let name = relative.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            if matcher.replace(&name, candidates) != name {
+                changes.renamed += 1;
+            }
+            // 逐级替换路径中的名称，上级目录改名后子路径随之改变
+            let target: PathBuf = relative
+                .iter()
+                .map(|c| matcher.replace(&c.to_string_lossy(), candidates))
+                .collect();
+            let target = output.join(target);
+            if *is_dir {
+                fs::create_dir_all(&target).with_context(|| format!("创建目录 {} 失败", target.display()))?;
+                continue;
+            }
+
+            let source = self.root.join(relative);
+            let bytes = fs::read(&source).with_context(|| format!("读取文件 {} 失败", source.display()))?;
+            let content = match text_content(&bytes) {
+                Some(text) => {
+                    let replaced = matcher.replace(&text.replace("{{", "\\{{"), candidates);
+                    if replaced != text {
+                        changes.files += 1;
+                    }
+                    replaced.into_bytes()
+                }
+                None => bytes,
+            };
+            fs::write(&target, content).with_context(|| format!("写入文件 {} 失败", target.display()))?;
+            // 保留源文件权限，例如脚本的可执行位
+            let permissions = fs::metadata(&source)
+                .with_context(|| format!("读取文件 {} 的元数据失败", source.display()))?
+                .permissions();
+            fs::set_permissions(&target, permissions)
+                .with_context(|| format!("设置文件 {} 的权限失败", target.display()))?;
+        }
+        Ok(changes)
+    }
+}
+
+/// 按词边界查找候选字符串，长的优先
+///
+/// 匹配前不能是字母或数字，匹配后不能是小写字母或数字，`MyAppConfig` 中的 `MyApp` 也会被替换
+struct Matcher {
+    regex: Option<Regex>,
+    /// 正则分组顺序对应的候选下标
+    order: Vec<usize>,
+    texts: Vec<String>,
+}
+
+impl Matcher {
+    fn new(candidates: &[Candidate]) -> Result<Self> {
+        let mut order: Vec<usize> = (0..candidates.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(candidates[i].text.len()));
+        let pattern = order.iter().map(|&i| regex::escape(&candidates[i].text)).collect::<Vec<_>>().join("|");
+        let regex = if order.is_empty() {
+            None
+        } else {
+            Some(Regex::new(&pattern).with_context(|| "编译候选字符串失败")?)
+        };
+        let texts = candidates.iter().map(|c| c.text.clone()).collect();
+        Ok(Self { regex, order, texts })
+    }
+
+    /// 返回每处匹配的起止位置和候选下标
+    fn find(&self, text: &str) -> Vec<(usize, usize, usize)> {
+        let Some(regex) = &self.regex else {
+            return Vec::new();
+        };
+        let mut found = Vec::new();
+        let mut position = 0;
+        while let Some(m) = regex.find_at(text, position) {
+            let before = text[..m.start()].chars().next_back();
+            let after = text[m.end()..].chars().next();
+            let bounded = !before.is_some_and(|c| c.is_alphanumeric())
+                && !after.is_some_and(|c| c.is_lowercase() || c.is_numeric());
+            if bounded {
+                let index = self.order.iter().copied().find(|&i| self.texts[i] == m.as_str());
+                if let Some(index) = index {
+                    found.push((m.start(), m.end(), index));
+                }
+                position = m.end();
+            } else {
+                position = m.start() + text[m.start()..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        found
+    }
+
+    /// 把匹配替换为对应的表达式
+    fn replace(&self, text: &str, candidates: &[Candidate]) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, end, index) in self.find(text) {
+            result.push_str(&text[last..start]);
+            result.push_str(&candidates[index].expression);
+            last = end;
+        }
+        result.push_str(&text[last..]);
+        result
+    }
+}
+
+/// 从包管理文件中读取包名和作者
+fn package_metadata(root: &Path) -> (Option<String>, Option<String>) {
+    let read = |file: &str| fs::read_to_string(root.join(file)).ok();
+
+    if let Some(content) = read("Cargo.toml") {
+        let package = toml_section(&content, "package");
+        let author = toml_value(&package, "authors")
+            .map(|authors| authors.trim_matches(['[', ']', '"', ' ']).to_string())
+            .map(|author| strip_email(&author));
+        return (toml_value(&package, "name"), author.filter(|a| !a.is_empty()));
+    }
+    if let Some(content) = read("package.json") {
+        let Ok(package) = serde_json::from_str::<Value>(&content) else {
+            return (None, None);
+        };
+        // `@scope/name` 只取包名部分
+        let name = package["name"].as_str().map(|n| n.rsplit('/').next().unwrap_or(n).to_string());
+        let author = match &package["author"] {
+            Value::String(author) => Some(strip_email(author)),
+            Value::Object(author) => author.get("name").and_then(Value::as_str).map(String::from),
+            _ => None,
+        };
+        return (name, author);
+    }
+    if let Some(content) = read("go.mod") {
+        let module = content.lines().find_map(|l| l.trim().strip_prefix("module ")).map(str::trim);
+        return (module.map(|m| m.rsplit('/').next().unwrap_or(m).to_string()), None);
+    }
+    if let Some(content) = read("pyproject.toml") {
+        return (toml_value(&toml_section(&content, "project"), "name"), None);
+    }
+    (None, None)
+}
+
+/// TOML 中某个表的内容，只处理 `[name]` 到下一个表头之间的行
+fn toml_section(content: &str, name: &str) -> String {
+    let header = format!("[{}]", name);
+    content
+        .lines()
+        .skip_while(|l| l.trim() != header)
+        .skip(1)
+        .take_while(|l| !l.trim_start().starts_with('['))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// TOML 表中 `key = "value"` 或 `key = [...]` 的值，字符串去掉引号
+fn toml_value(section: &str, key: &str) -> Option<String> {
+    section.lines().find_map(|line| {
+        let (k, v) = line.split_once('=')?;
+        (k.trim() == key).then(|| v.trim().trim_matches('"').to_string())
+    })
+}
+
+/// 去掉 `Name <email>` 中的邮箱
+fn strip_email(author: &str) -> String {
+    author.split('<').next().unwrap_or(author).trim().to_string()
+}
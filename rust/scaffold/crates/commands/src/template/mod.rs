@@ -0,0 +1,29 @@
+pub mod check;
+pub mod handler;
+pub mod init;
+pub mod snapshot;
+
+use std::path::PathBuf;
+use anyhow::Result;
+use crate::output::OutputFormat;
+use handler::TemplateHandler;
+
+/// 打印 scaffold.json 的 JSON Schema
+pub fn schema() -> Result<()> {
+    TemplateHandler::new().schema()
+}
+
+/// 校验模板目录
+pub fn validate(dir: PathBuf, format: OutputFormat) -> Result<()> {
+    TemplateHandler::new().validate(&dir, format)
+}
+
+/// 由已有项目生成模板，写入 `output`，项目本身不会被修改
+pub fn init(dir: PathBuf, output: Option<PathBuf>, yes: bool) -> Result<()> {
+    TemplateHandler::new().init(&dir, output.as_deref(), yes)
+}
+
+/// 运行模板的快照测试，`bless` 为 true 时更新快照
+pub fn test(dir: PathBuf, bless: bool) -> Result<()> {
+    TemplateHandler::new().test(&dir, bless)
+}
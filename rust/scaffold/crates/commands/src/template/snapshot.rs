@@ -0,0 +1,123 @@
+//! 模板快照测试
+//!
+//! 模板目录下的 `tests/<用例>.json` 是一组答案，`tests/<用例>/` 是用这组答案生成的项目快照。
+//! 两者都属于模板自身的测试，不会生成到项目中，也不参与校验
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{Result, Context};
+use walkdir::WalkDir;
+use crate::config;
+
+/// 测试用例
+pub struct Fixture {
+    /// 用例名，即答案文件名去掉 `.json`
+    pub name: String,
+    /// 答案文件
+    pub answers: PathBuf,
+    /// 快照目录
+    pub snapshot: PathBuf,
+}
+
+/// 生成结果与快照的差异
+pub enum Difference {
+    /// 生成了快照中没有的文件
+    Added(PathBuf),
+    /// 快照中的文件没有生成
+    Removed(PathBuf),
+    /// 文件内容不同
+    Changed { path: PathBuf, expected: Vec<u8>, actual: Vec<u8> },
+}
+
+/// 按用例名排序列出模板的测试用例
+pub fn fixtures(template_dir: &Path) -> Result<Vec<Fixture>> {
+    let tests_dir = template_dir.join(config::TEMPLATE_TESTS_DIR);
+    if !tests_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut fixtures = Vec::new();
+    for entry in fs::read_dir(&tests_dir).with_context(|| format!("读取目录 {} 失败", tests_dir.display()))? {
+        let path = entry.with_context(|| format!("读取目录 {} 失败", tests_dir.display()))?.path();
+        if !path.is_file() || path.extension().is_none_or(|e| e != "json") {
+            continue;
+        }
+        let Some(name) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else { continue };
+        fixtures.push(Fixture { snapshot: tests_dir.join(&name), answers: path, name });
+    }
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(fixtures)
+}
+
+/// 相对模板目录的路径是否属于测试用例：`tests/<用例>.json` 或 `tests/<用例>/` 及其内容
+pub fn is_fixture_path(template_dir: &Path, relative: &Path) -> bool {
+    let mut components = relative.components();
+    if components.next().is_none_or(|c| c.as_os_str() != config::TEMPLATE_TESTS_DIR) {
+        return false;
+    }
+    let Some(name) = components.next() else { return false };
+    let name = Path::new(name.as_os_str());
+    let tests_dir = template_dir.join(config::TEMPLATE_TESTS_DIR);
+    if name.extension().is_some_and(|e| e == "json") {
+        return tests_dir.join(name).is_file();
+    }
+    tests_dir.join(name).with_extension("json").is_file() && tests_dir.join(name).is_dir()
+}
+
+/// 比较生成的项目与快照，只比较文件，忽略 `.git`
+pub fn compare(actual: &Path, snapshot: &Path) -> Result<Vec<Difference>> {
+    let mut actual_files = files(actual)?;
+    let mut differences = Vec::new();
+
+    for (path, expected) in files(snapshot)? {
+        match actual_files.remove(&path) {
+            Some(actual) if actual == expected => {}
+            Some(actual) => differences.push(Difference::Changed { path, expected, actual }),
+            None => differences.push(Difference::Removed(path)),
+        }
+    }
+    differences.extend(actual_files.into_keys().map(Difference::Added));
+    differences.sort_by(|a, b| a.path().cmp(b.path()));
+    Ok(differences)
+}
+
+/// 用生成的项目替换快照
+pub fn bless(actual: &Path, snapshot: &Path) -> Result<()> {
+    if snapshot.exists() {
+        fs::remove_dir_all(snapshot).with_context(|| format!("删除快照 {} 失败", snapshot.display()))?;
+    }
+    for (relative, content) in files(actual)? {
+        let path = snapshot.join(&relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("创建目录 {} 失败", parent.display()))?;
+        }
+        fs::write(&path, content).with_context(|| format!("写入文件 {} 失败", path.display()))?;
+    }
+    Ok(())
+}
+
+impl Difference {
+    pub fn path(&self) -> &Path {
+        match self {
+            Difference::Added(path) | Difference::Removed(path) => path,
+            Difference::Changed { path, .. } => path,
+        }
+    }
+}
+
+/// 目录中的全部文件及其内容，键为相对路径
+fn files(root: &Path) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+    let walker = WalkDir::new(root).min_depth(1).into_iter().filter_entry(|e| e.file_name() != ".git");
+    for entry in walker {
+        let entry = entry.with_context(|| format!("遍历目录 {} 失败", root.display()))?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path()).to_path_buf();
+        let content = fs::read(entry.path()).with_context(|| format!("读取文件 {} 失败", entry.path().display()))?;
+        files.insert(relative, content);
+    }
+    Ok(files)
+}
@@ -0,0 +1,150 @@
+//! 模板钩子信任记录
+//!
+//! 钩子会在本机执行任意命令，第一次执行某个模板的钩子前需要用户确认。
+//! 确认结果按模板来源和模板内容哈希保存在 `~/.scaffold/trust.json`，模板内容变化后需要重新确认
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+/// 一条信任记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustEntry {
+    /// 模板来源（仓库 URL 或本地路径）
+    pub source: String,
+    /// 模板名称，仅用于展示
+    pub template: String,
+    /// 模板目录内容的 SHA-256
+    pub hash: String,
+    /// 确认时间（Unix 秒）
+    pub trusted_at: u64,
+}
+
+/// 信任记录文件
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TrustStore {
+    #[serde(default)]
+    pub trusted: Vec<TrustEntry>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl TrustStore {
+    /// 读取信任记录，文件不存在时返回空记录
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut store = if path.exists() {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("读取信任记录 {} 失败", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("解析信任记录 {} 失败", path.display()))?
+        } else {
+            Self::default()
+        };
+        store.path = path.to_path_buf();
+        Ok(store)
+    }
+
+    /// 该来源、该内容的模板是否已被信任
+    pub fn is_trusted(&self, source: &str, hash: &str) -> bool {
+        self.trusted.iter().any(|e| e.source == source && e.hash == hash)
+    }
+
+    /// 记录信任并写回文件，同一来源同名模板的旧记录会被替换
+    pub fn trust(&mut self, source: &str, template: &str, hash: &str) -> Result<()> {
+        self.trusted.retain(|e| !(e.source == source && e.template == template));
+        self.trusted.push(TrustEntry {
+            source: source.to_string(),
+            template: template.to_string(),
+            hash: hash.to_string(),
+            trusted_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        });
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("创建目录 {} 失败", parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self).with_context(|| "序列化信任记录失败")?;
+        fs::write(&self.path, content)
+            .with_context(|| format!("写入信任记录 {} 失败", self.path.display()))
+    }
+}
+
+/// 计算模板目录内容的哈希
+///
+/// 按路径排序依次加入相对路径和文件内容，任何文件（包括 scaffold.json）变化都会改变哈希
+pub fn template_hash(dir: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    // 跳过 `.git`，本地模板提交后哈希不变
+    let walker = WalkDir::new(dir)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git");
+    for entry in walker {
+        let entry = entry.with_context(|| format!("遍历模板目录 {} 失败", dir.display()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+        let content = fs::read(entry.path())
+            .with_context(|| format!("读取文件 {} 失败", entry.path().display()))?;
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trust_round_trips_and_replaces_old_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scaffold/trust.json");
+        let mut store = TrustStore::load(&path).unwrap();
+        assert!(store.trusted.is_empty());
+
+        store.trust("https://example.com/t.git", "rust-cli", "aaa").unwrap();
+        store.trust("https://example.com/t.git", "rust-cli", "bbb").unwrap();
+        store.trust("https://example.com/other.git", "rust-cli", "aaa").unwrap();
+
+        let store = TrustStore::load(&path).unwrap();
+        assert_eq!(store.trusted.len(), 2);
+        assert!(store.is_trusted("https://example.com/t.git", "bbb"));
+        assert!(!store.is_trusted("https://example.com/t.git", "aaa"));
+        assert!(store.is_trusted("https://example.com/other.git", "aaa"));
+    }
+
+    #[test]
+    fn hash_tracks_content_and_paths_but_not_git() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("scaffold.json"), "{}").unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        let original = template_hash(dir.path()).unwrap();
+
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join(".git/HEAD"), "ref: refs/heads/main").unwrap();
+        assert_eq!(template_hash(dir.path()).unwrap(), original);
+
+        fs::write(dir.path().join("src/main.rs"), "fn main() { }").unwrap();
+        let changed = template_hash(dir.path()).unwrap();
+        assert_ne!(changed, original);
+
+        fs::rename(dir.path().join("src/main.rs"), dir.path().join("src/lib.rs")).unwrap();
+        assert_ne!(template_hash(dir.path()).unwrap(), changed);
+    }
+}
@@ -1,44 +1,74 @@
 use std::fs;
-use std::path::Path;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use anyhow::{Result, Context};
+use colored::{Color, Colorize};
 use fs_extra::dir::remove;
-use serde::{Deserialize, Serialize};
 use indicatif::{ProgressBar, ProgressStyle};
-use utils::{git::GitRepo, info};
+use model::index::{short_commit, DEFAULT_SOURCE_URL};
+use model::{Source, TemplateEntry, TemplateIndex, TemplateManifest};
+use utils::{debug, git::GitRepo, info, warn};
 use crate::config;
+use crate::discovery::discover;
+use crate::output::Table;
 
 pub struct UpdateHandler {
     home_dir: String,
+    /// 未配置模板来源时使用的默认来源地址
+    source_url: String,
 }
 
+/// 无效的模板配置
+struct ConfigError {
+    /// scaffold.json 路径
+    path: PathBuf,
+    /// 出错的行号，读取失败时为 None
+    line: Option<usize>,
+    /// 出错的列号，读取失败时为 None
+    column: Option<usize>,
+    message: String,
+}
+
+/// 部分模板的 scaffold.json 无效，其余模板已正常更新
+#[derive(Debug)]
+pub struct InvalidTemplates {
+    pub count: usize,
+}
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ScaffoldConfig {
-    pub name: String,
-    pub description: Option<String>,
-    pub version: Option<String>,
-    pub author: Option<String>,
-    pub language: Option<String>,
-    pub tags: Option<Vec<String>>,
-    #[serde(skip_deserializing)]
-    #[serde(default = "default_path")]
-    pub path: String,
+impl InvalidTemplates {
+    /// 进程退出码，与其他错误的退出码 1 区分
+    pub const EXIT_CODE: i32 = 3;
+}
+
+impl fmt::Display for InvalidTemplates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} 个模板的 {} 无效，已跳过", self.count, config::SCAFFOLD_CONFIG_FILE)
+    }
 }
 
-fn default_path() -> String {
-    String::new()
+impl std::error::Error for InvalidTemplates {}
+
+/// 单个来源的更新结果
+struct SourceUpdate {
+    /// 更新前的提交，重新克隆时为 None
+    old: Option<String>,
+    /// 更新后的提交
+    new: String,
 }
 
 impl UpdateHandler {
-    pub fn new() -> Self {
-        let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        Self { home_dir }
+    /// 在 `home_dir` 下保存模板代码和索引，未配置来源时从 `source_url` 下载
+    pub fn new(home_dir: &Path, source_url: &str) -> Self {
+        Self {
+            home_dir: home_dir.to_string_lossy().to_string(),
+            source_url: source_url.to_string(),
+        }
     }
 
     pub fn execute(&self) -> Result<()> {
         info!("开始更新代码仓库...");
 
-        // 1. 下载GitHub代码到临时目录
+        // 1. 每个来源增量同步到 codes-temp 下的同名目录，全部成功后再与 codes、codes-prev 轮换
         let temp_dir = format!("{}/{}/{}{}", self.home_dir, config::SCAFFOLD_DIR_NAME, config::CODES_DIR_NAME, config::TEMP_DIR_SUFFIX);
         let target_dir = format!("{}/{}/{}", self.home_dir, config::SCAFFOLD_DIR_NAME, config::CODES_DIR_NAME);
 
@@ -49,7 +79,14 @@ impl UpdateHandler {
                 .with_context(|| format!("创建{}目录失败: {}", config::SCAFFOLD_DIR_NAME, scaffold_dir))?;
         }
 
-        // 2. 克隆GitHub仓库到临时目录
+        // 2. 逐个同步模板来源到临时目录，已有检出不可复用时重新克隆
+        let config_file = format!("{}/{}", self.home_dir, config::HOME_SCAFFOLD_CONFIG);
+        let index = TemplateIndex::load(Path::new(&config_file))?;
+        let sources = self.sources(&index);
+        if sources.is_empty() {
+            anyhow::bail!("未配置任何模板来源，请先运行 'scaffold source add <名称> <URL>'");
+        }
+
         let spinner = ProgressBar::new_spinner();
         spinner.set_style(
             ProgressStyle::default_spinner()
@@ -57,27 +94,26 @@ impl UpdateHandler {
                 .expect("Invalid template")
                 .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
         );
-        spinner.set_message(format!("正在下载GitHub代码到 {}...", temp_dir));
         spinner.enable_steady_tick(std::time::Duration::from_millis(100));
-        self.clone_repository(&temp_dir)?;
-        spinner.finish_with_message("代码下载完成");
-
-        // 3. 删除旧的codes目录
-        if Path::new(&target_dir).exists() {
-            let spinner = ProgressBar::new_spinner();
-            spinner.set_style(
-                ProgressStyle::default_spinner()
-                    .template("{spinner:.yellow} {msg}")
-                    .expect("Invalid template")
-                    .tick_strings(&["◐", "◓", "◑", "◒"])
-            );
-            spinner.set_message("正在删除旧的codes目录...");
-            spinner.enable_steady_tick(std::time::Duration::from_millis(150));
-            self.remove_directory(&target_dir)?;
-            spinner.finish_with_message("旧目录已删除");
+        if Path::new(&temp_dir).exists() {
+            self.remove_directory(&temp_dir)?;
         }
+        let mut updates = Vec::new();
+        for source in &sources {
+            spinner.set_message(format!("正在更新 {} ({})...", source.name, source.url));
+            let update = self.update_source(
+                source,
+                &format!("{}/{}", target_dir, source.name),
+                &format!("{}/{}", temp_dir, source.name),
+            )?;
+            info!(source = %source.name, old = ?update.old, new = %update.new, "已更新模板来源");
+            updates.push(update);
+        }
+        spinner.finish_with_message(format!("{} 个来源下载完成", sources.len()));
+        print_updates(&sources, &updates);
 
-        // 4. 重命名临时目录为codes
+        // 3. 旧的codes目录保留为codes-prev，再将临时目录重命名为codes
+        let prev_dir = self.prev_dir();
         let spinner = ProgressBar::new_spinner();
         spinner.set_style(
             ProgressStyle::default_spinner()
@@ -85,12 +121,134 @@ impl UpdateHandler {
                 .expect("Invalid template")
                 .tick_strings(&["◰", "◳", "◲", "◱"])
         );
-        spinner.set_message("正在重命名目录...");
+        spinner.set_message("正在替换codes目录...");
         spinner.enable_steady_tick(std::time::Duration::from_millis(120));
-        self.rename_directory(&temp_dir, &target_dir)?;
-        spinner.finish_with_message("目录重命名完成");
+        let has_snapshot = self.swap_in(&temp_dir, &target_dir, &prev_dir)?;
+        spinner.finish_with_message("codes目录替换完成");
+
+        // 4. 解析codes目录下的所有子目录并写入 ~/.scaffold/config.json，失败时恢复更新前的codes
+        //    单个模板的 scaffold.json 无效时跳过该模板，最后统一报告
+        let (index, errors) = match self.reindex(&target_dir, index.sources, &config_file) {
+            Ok(result) => result,
+            Err(e) if has_snapshot => {
+                self.restore(&target_dir, &prev_dir)?;
+                return Err(e.context("新模板解析失败，已恢复更新前的模板"));
+            }
+            Err(e) => return Err(e),
+        };
+
+        for config in &index.templates {
+            info!(
+                source = %config.source.as_deref().unwrap_or_default(),
+                name = %config.name(),
+                description = %config.manifest.description.as_deref().unwrap_or("无描述"),
+                path = %config.path,
+                "找到模板配置"
+            );
+        }
+
+        info!("代码仓库更新完成！");
+        report_config_errors(&target_dir, &errors)
+    }
+
+    /// 回滚到上一次更新前的模板
+    ///
+    /// 交换codes和codes-prev，再按回滚后的codes重建模板索引；再次回滚会回到回滚前的状态
+    pub fn rollback(&self) -> Result<()> {
+        let target_dir = format!("{}/{}/{}", self.home_dir, config::SCAFFOLD_DIR_NAME, config::CODES_DIR_NAME);
+        let temp_dir = format!("{}{}", target_dir, config::TEMP_DIR_SUFFIX);
+        let prev_dir = self.prev_dir();
+        if !Path::new(&prev_dir).exists() {
+            anyhow::bail!("没有可回滚的模板快照：{} 不存在", prev_dir);
+        }
+
+        let config_file = format!("{}/{}", self.home_dir, config::HOME_SCAFFOLD_CONFIG);
+        let index = TemplateIndex::load(Path::new(&config_file))?;
+        let sources = self.sources(&index);
+
+        // codes -> codes-temp，codes-prev -> codes，codes-temp -> codes-prev
+        if Path::new(&temp_dir).exists() {
+            self.remove_directory(&temp_dir)?;
+        }
+        let has_current = Path::new(&target_dir).exists();
+        if has_current {
+            self.rename_directory(&target_dir, &temp_dir)?;
+        }
+        if let Err(e) = self.rename_directory(&prev_dir, &target_dir) {
+            if has_current {
+                self.rename_directory(&temp_dir, &target_dir)?;
+            }
+            return Err(e);
+        }
+        if has_current {
+            self.rename_directory(&temp_dir, &prev_dir)?;
+        }
+
+        let updates: Vec<SourceUpdate> = sources
+            .iter()
+            .map(|source| SourceUpdate {
+                old: GitRepo::head_commit(&format!("{}/{}", prev_dir, source.name)).ok(),
+                new: GitRepo::head_commit(&format!("{}/{}", target_dir, source.name)).unwrap_or_default(),
+            })
+            .collect();
+        print_updates(&sources, &updates);
+
+        let (index, errors) = self.reindex(&target_dir, index.sources, &config_file)?;
+        info!(templates = index.templates.len(), "已回滚到上一次更新前的模板");
+        report_config_errors(&target_dir, &errors)
+    }
+
+    /// 要更新的模板来源，未配置时为使用 `source_url` 的默认来源
+    fn sources(&self, index: &TemplateIndex) -> Vec<Source> {
+        index
+            .sources
+            .clone()
+            .unwrap_or_else(|| vec![Source { url: self.source_url.clone(), ..Source::default_source() }])
+    }
+
+    /// codes-prev 快照目录
+    fn prev_dir(&self) -> String {
+        format!("{}/{}/{}{}", self.home_dir, config::SCAFFOLD_DIR_NAME, config::CODES_DIR_NAME, config::PREV_DIR_SUFFIX)
+    }
+
+    /// 用新目录替换codes，旧的codes保留为codes-prev
+    ///
+    /// 第二次重命名失败时把codes-prev移回原处。返回是否保留了快照
+    fn swap_in(&self, new_dir: &str, target_dir: &str, prev_dir: &str) -> Result<bool> {
+        let has_current = Path::new(target_dir).exists();
+        if has_current {
+            if Path::new(prev_dir).exists() {
+                self.remove_directory(prev_dir)?;
+            }
+            self.rename_directory(target_dir, prev_dir)?;
+        }
+
+        if let Err(e) = self.rename_directory(new_dir, target_dir) {
+            if has_current {
+                self.rename_directory(prev_dir, target_dir)
+                    .with_context(|| format!("恢复 {} 失败，更新前的模板保存在 {}", target_dir, prev_dir))?;
+            }
+            return Err(e);
+        }
+        Ok(has_current)
+    }
 
-        // 5. 解析codes目录下的所有子目录，获取scaffold.json
+    /// 删除新的codes，将codes-prev移回codes
+    fn restore(&self, target_dir: &str, prev_dir: &str) -> Result<()> {
+        warn!(dir = %target_dir, "恢复更新前的模板");
+        if Path::new(target_dir).exists() {
+            self.remove_directory(target_dir)?;
+        }
+        self.rename_directory(prev_dir, target_dir)
+    }
+
+    /// 解析codes目录下每个来源的模板并写入索引，同时返回无效的模板配置
+    fn reindex(
+        &self,
+        target_dir: &str,
+        sources: Option<Vec<Source>>,
+        config_file: &str,
+    ) -> Result<(TemplateIndex, Vec<ConfigError>)> {
         let spinner = ProgressBar::new_spinner();
         spinner.set_style(
             ProgressStyle::default_spinner()
@@ -100,61 +258,94 @@ impl UpdateHandler {
         );
         spinner.set_message("正在解析scaffold配置...");
         spinner.enable_steady_tick(std::time::Duration::from_millis(80));
-        let scaffold_configs = self.parse_scaffold_configs(&target_dir)?;
-        spinner.finish_with_message(format!("找到 {} 个scaffold配置", scaffold_configs.len()));
-
-        // 6. 写入配置到 ~/.scaffold/config.json
-        if !scaffold_configs.is_empty() {
-            let scaffold_dir = format!("{}/{}", self.home_dir, config::HOME_SCAFFOLD_DIR);
-            let config_file = format!("{}/{}", self.home_dir, config::HOME_SCAFFOLD_CONFIG);
-            spinner.set_message("正在保存配置到文件...");
-            spinner.enable_steady_tick(std::time::Duration::from_millis(50));
+        let mut index = TemplateIndex { sources, ..TemplateIndex::default() };
+        let mut errors = Vec::new();
+        for source in self.sources(&index) {
+            let source_dir = format!("{}/{}", target_dir, source.name);
+            if !Path::new(&source_dir).exists() {
+                warn!(source = %source.name, dir = %source_dir, "来源目录不存在，请运行 scaffold update");
+                continue;
+            }
+            let commit = GitRepo::head_commit(&source_dir)?;
+            let mut configs = self.parse_scaffold_configs(&source_dir, &source, &mut errors)?;
+            for config in &mut configs {
+                config.commit = Some(commit.clone());
+            }
+            index.templates.extend(configs);
+        }
+        spinner.finish_with_message(format!("找到 {} 个scaffold配置", index.templates.len()));
+
+        // 没有有效模板时同样写入，避免索引仍指向旧的提交和清单
+        spinner.set_message("正在保存配置到文件...");
+        spinner.enable_steady_tick(std::time::Duration::from_millis(50));
+        index.save(Path::new(config_file))?;
+        spinner.finish_with_message(format!("配置已保存到 {}", config_file));
+        Ok((index, errors))
+    }
 
-            self.write_scaffold_configs(&scaffold_dir, &config_file, &scaffold_configs)?;
-            spinner.finish_with_message(format!("配置已保存到 {}", config_file));
+    /// 在 `work_dir` 中准备来源的最新代码
+    ///
+    /// `current_dir` 是该来源可复用的检出时，以硬链接的本地克隆复制到 `work_dir`，再 fetch 并重置到目标提交；
+    /// 远程地址变化、检出损坏或增量更新失败时重新克隆。`current_dir` 本身不会被修改
+    fn update_source(&self, source: &Source, current_dir: &str, work_dir: &str) -> Result<SourceUpdate> {
+        let (url, reference) = source.remote();
+        if Path::new(work_dir).exists() {
+            self.remove_directory(work_dir)?;
         }
 
-        for config in &scaffold_configs {
-            info!(
-                name = %config.name,
-                description = %config.description.as_ref().unwrap_or(&"无描述".to_string()),
-                path = %config.path,
-                "找到模板配置"
-            );
+        if let Some(old) = self.reusable_checkout(current_dir, url) {
+            let synced = GitRepo::new(url)
+                .clone_local(current_dir, work_dir)
+                .and_then(|_| GitRepo::sync(work_dir, reference));
+            match synced {
+                Ok(new) => return Ok(SourceUpdate { old: Some(old), new }),
+                Err(e) => {
+                    warn!(source = %source.name, error = %format!("{:#}", e), "增量更新失败，重新克隆");
+                }
+            }
         }
 
-        info!("代码仓库更新完成！");
-        Ok(())
+        let new = self.clone_repository(url, reference, work_dir)?;
+        Ok(SourceUpdate { old: None, new })
     }
 
-    fn write_scaffold_configs(&self, scaffold_dir: &str, config_file: &str, configs: &[ScaffoldConfig]) -> Result<()> {
-        // 确保 .scaffold 目录存在
-        if !Path::new(scaffold_dir).exists() {
-            fs::create_dir_all(scaffold_dir)
-                .with_context(|| format!("创建目录 {} 失败", scaffold_dir))?;
+    /// 已有检出可以增量更新时返回其当前提交
+    fn reusable_checkout(&self, dir: &str, url: &str) -> Option<String> {
+        if !Path::new(dir).join(".git").exists() {
+            return None;
         }
 
-        let json_content = serde_json::to_string_pretty(configs)
-            .with_context(|| "序列化配置失败")?;
-
-        fs::write(config_file, json_content)
-            .with_context(|| format!("写入配置文件 {} 失败", config_file))?;
+        let remote = match GitRepo::remote_url(dir) {
+            Ok(remote) => remote,
+            Err(e) => {
+                warn!(dir = %dir, error = %format!("{:#}", e), "本地检出已损坏，重新克隆");
+                return None;
+            }
+        };
+        if remote != url {
+            info!(dir = %dir, old = %remote, new = %url, "来源地址已变化，重新克隆");
+            return None;
+        }
 
-        Ok(())
+        match GitRepo::head_commit(dir) {
+            Ok(commit) => Some(commit),
+            Err(e) => {
+                warn!(dir = %dir, error = %format!("{:#}", e), "本地检出已损坏，重新克隆");
+                None
+            }
+        }
     }
 
-    fn clone_repository(&self, target_dir: &str) -> Result<()> {
+    fn clone_repository(&self, url: &str, reference: Option<&str>, target_dir: &str) -> Result<String> {
         // 如果临时目录已存在，先删除
         if Path::new(target_dir).exists() {
             self.remove_directory(target_dir)?;
         }
 
-        // 使用GitRepo克隆代码仓库
-        let repo = GitRepo::new(config::GITHUB_REPO_URL);
-        repo.clone_to(target_dir)
-            .with_context(|| format!("克隆仓库到 {} 失败", target_dir))?;
-
-        Ok(())
+        // 使用GitRepo克隆代码仓库并检出指定的 ref
+        let repo = GitRepo::new(url);
+        repo.clone_at(target_dir, reference)
+            .with_context(|| format!("克隆仓库 {} 到 {} 失败", url, target_dir))
     }
 
     fn remove_directory(&self, dir_path: &str) -> Result<()> {
@@ -169,50 +360,109 @@ impl UpdateHandler {
         Ok(())
     }
 
-    fn parse_scaffold_configs(&self, codes_dir: &str) -> Result<Vec<ScaffoldConfig>> {
+    /// 解析来源目录下的所有模板配置，无效的配置记录到 `errors` 后跳过
+    fn parse_scaffold_configs(
+        &self,
+        codes_dir: &str,
+        source: &Source,
+        errors: &mut Vec<ConfigError>,
+    ) -> Result<Vec<TemplateEntry>> {
         let mut configs = Vec::new();
-        let entries = fs::read_dir(codes_dir)
-            .with_context(|| format!("读取目录 {} 失败", codes_dir))?;
-
-        for entry in entries {
-            let entry = entry.with_context(|| "读取目录条目失败")?;
-            let path = entry.path();
-
-            // 只处理一级子目录
-            if path.is_dir() {
-                if let Some(config) = self.read_scaffold_config(&path)? {
+        for template in discover(Path::new(codes_dir), config::TEMPLATE_MAX_DEPTH)? {
+            match self.read_scaffold_config(&template.path, source) {
+                Ok(mut config) => {
+                    config.category = template.category;
                     configs.push(config);
                 }
+                Err(error) => {
+                    debug!(path = %error.path.display(), error = %error.message, "模板配置无效，已跳过");
+                    errors.push(error);
+                }
             }
         }
 
         Ok(configs)
     }
 
-    fn read_scaffold_config(&self, dir_path: &Path) -> Result<Option<ScaffoldConfig>> {
+    fn read_scaffold_config(&self, dir_path: &Path, source: &Source) -> std::result::Result<TemplateEntry, ConfigError> {
         let config_path = dir_path.join(config::SCAFFOLD_CONFIG_FILE);
+        let content = fs::read_to_string(&config_path).map_err(|e| ConfigError {
+            path: config_path.clone(),
+            line: None,
+            column: None,
+            message: e.to_string(),
+        })?;
+
+        let manifest: TemplateManifest = serde_json::from_str(&content).map_err(|e| {
+            // serde_json 的错误信息末尾带有位置，表格中单独列出
+            let position = format!(" at line {} column {}", e.line(), e.column());
+            let message = e.to_string();
+            ConfigError {
+                path: config_path.clone(),
+                line: Some(e.line()),
+                column: Some(e.column()),
+                message: message.strip_suffix(&position).unwrap_or(&message).to_string(),
+            }
+        })?;
+        manifest.check_version().map_err(|e| ConfigError {
+            path: config_path.clone(),
+            line: None,
+            column: None,
+            message: e.to_string(),
+        })?;
+
+        // 设置模板路径和来源
+        Ok(TemplateEntry {
+            source: Some(source.name.clone()),
+            source_url: Some(source.remote().0.to_string()),
+            ..TemplateEntry::local(manifest, dir_path)
+        })
+    }
+}
 
-        // 如果配置文件不存在，返回 None
-        if !config_path.exists() {
-            return Ok(None);
-        }
-
-        let content = fs::read_to_string(&config_path)
-            .with_context(|| format!("读取文件 {} 失败", config_path.display()))?;
-
-        let mut config: ScaffoldConfig = serde_json::from_str(&content)
-            .with_context(|| format!("解析{}失败: {}", config::SCAFFOLD_CONFIG_FILE, config_path.display()))?;
 
-        // 设置模板路径
-        config.path = dir_path.to_string_lossy().to_string();
+impl Default for UpdateHandler {
+    fn default() -> Self {
+        let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        Self::new(Path::new(&home_dir), DEFAULT_SOURCE_URL)
+    }
+}
 
-        Ok(Some(config))
+/// 打印每个来源更新前后的提交
+fn print_updates(sources: &[Source], updates: &[SourceUpdate]) {
+    let width = sources.iter().map(|s| s.name.len()).max().unwrap_or_default();
+    for (source, update) in sources.iter().zip(updates) {
+        let new = short_commit(&update.new);
+        let status = match &update.old {
+            Some(old) if *old == update.new => format!("{} 已是最新", new).dimmed().to_string(),
+            Some(old) => format!("{} → {}", short_commit(old), new.green()),
+            None => format!("{} 已克隆", new.green()),
+        };
+        println!("{:width$}  {}", source.name.cyan(), status, width = width);
     }
 }
 
+/// 打印无效模板配置的汇总表，存在无效配置时返回 [`InvalidTemplates`]
+fn report_config_errors(codes_dir: &str, errors: &[ConfigError]) -> Result<()> {
+    if errors.is_empty() {
+        return Ok(());
+    }
 
-impl Default for UpdateHandler {
-    fn default() -> Self {
-        Self::new()
+    let mut table = Table::new(&["文件", "行", "列", "错误"]).color(0, Color::Yellow);
+    for error in errors {
+        let path = error.path.strip_prefix(codes_dir).unwrap_or(&error.path);
+        let position = |n: Option<usize>| n.map_or_else(|| "-".to_string(), |n| n.to_string());
+        table.row(vec![
+            path.display().to_string(),
+            position(error.line),
+            position(error.column),
+            error.message.clone(),
+        ]);
     }
-}
\ No newline at end of file
+
+    println!();
+    println!("{}", format!("以下 {} 个模板配置无效，未加入索引:", errors.len()).red().bold());
+    table.print();
+
+    Err(InvalidTemplates { count: errors.len() }.into())
+}
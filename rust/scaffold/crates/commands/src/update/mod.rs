@@ -3,8 +3,16 @@ pub mod handler;
 use anyhow::Result;
 use handler::UpdateHandler;
 
+pub use handler::InvalidTemplates;
+
 /// 更新本地代码仓库
 pub fn update() -> Result<()> {
-    let handler = UpdateHandler::new();
+    let handler = UpdateHandler::default();
     handler.execute()
-}
\ No newline at end of file
+}
+
+/// 回滚到上一次更新前的模板
+pub fn rollback() -> Result<()> {
+    let handler = UpdateHandler::default();
+    handler.rollback()
+}
@@ -0,0 +1,362 @@
+//! update → list → new 的完整流程测试
+//!
+//! 模板来源是临时目录中的本地裸仓库，主目录和工作目录也都在临时目录中，不访问网络
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use commands::list::handler::ListHandler;
+use commands::list::ListOptions;
+use commands::new::handler::NewHandler;
+use commands::new::conflict::ConflictPolicy;
+use commands::new::NewOptions;
+use commands::update::handler::UpdateHandler;
+use commands::template::handler::TemplateHandler;
+use commands::trust::template_hash;
+use commands::update::InvalidTemplates;
+use model::TemplateIndex;
+use serde_json::json;
+use tempfile::TempDir;
+
+/// 模板来源仓库：在工作副本中提交，再推送到裸仓库
+struct SourceRepo {
+    work: PathBuf,
+    bare: PathBuf,
+}
+
+impl SourceRepo {
+    fn new(root: &Path) -> Self {
+        let work = root.join("source");
+        let bare = root.join("source.git");
+        fs::create_dir_all(&work).unwrap();
+        git(&work, &["init", "--quiet", "--initial-branch=main"]);
+        Self { work, bare }
+    }
+
+    fn write(&self, relative: &str, content: &str) -> &Self {
+        let path = self.work.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+        self
+    }
+
+    fn manifest(&self, dir: &str, manifest: serde_json::Value) -> &Self {
+        self.write(&format!("{}/scaffold.json", dir), &serde_json::to_string_pretty(&manifest).unwrap())
+    }
+
+    /// 提交全部改动并同步到裸仓库，返回提交 SHA
+    fn commit(&self, message: &str) -> String {
+        git(&self.work, &["add", "-A"]);
+        git(&self.work, &["commit", "--quiet", "-m", message]);
+        if self.bare.exists() {
+            git(&self.work, &["push", "--quiet", self.bare.to_str().unwrap(), "main"]);
+        } else {
+            git(&self.work, &["clone", "--quiet", "--bare", ".", self.bare.to_str().unwrap()]);
+        }
+        git(&self.work, &["rev-parse", "HEAD"])
+    }
+
+    fn url(&self) -> &str {
+        self.bare.to_str().unwrap()
+    }
+}
+
+/// 一次测试使用的主目录、工作目录和模板来源
+struct Env {
+    _root: TempDir,
+    home: PathBuf,
+    work: PathBuf,
+    source: SourceRepo,
+}
+
+impl Env {
+    fn new() -> Self {
+        let root = tempfile::tempdir().unwrap();
+        let home = root.path().join("home");
+        let work = root.path().join("work");
+        fs::create_dir_all(&home).unwrap();
+        fs::create_dir_all(&work).unwrap();
+        let source = SourceRepo::new(root.path());
+        Self { home, work, source, _root: root }
+    }
+
+    fn update(&self) -> anyhow::Result<()> {
+        UpdateHandler::new(&self.home, self.source.url()).execute()
+    }
+
+    fn rollback(&self) -> anyhow::Result<()> {
+        UpdateHandler::new(&self.home, self.source.url()).rollback()
+    }
+
+    fn list(&self, options: ListOptions) -> Vec<String> {
+        let templates = ListHandler::new(&self.home).templates(&options).unwrap();
+        templates.iter().map(|t| t.qualified_name()).collect()
+    }
+
+    fn index(&self) -> TemplateIndex {
+        TemplateIndex::load(&self.home.join(".scaffold/config.json")).unwrap()
+    }
+
+    fn new_project(&self, template: &str, name: &str, set: &[&str]) -> anyhow::Result<PathBuf> {
+        let options = NewOptions {
+            set: set.iter().map(|s| s.to_string()).collect(),
+            yes: true,
+            no_input: true,
+            ..Default::default()
+        };
+        NewHandler::new(&self.home, &self.work).execute(Some(template.to_string()), Some(name.to_string()), options)?;
+        Ok(self.work.join(name))
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(["-c", "user.name=scaffold", "-c", "user.email=scaffold@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// 包含一个根目录模板和一个分类下模板的来源
+fn standard_source(env: &Env) -> String {
+    env.source
+        .manifest("rust-cli", json!({
+            "name": "rust-cli",
+            "language": "Rust",
+            "tags": ["cli"],
+            "variables": [
+                { "name": "crate_name", "type": "string", "default": "{{snakeCase project_name}}" },
+                { "name": "use_docker", "type": "bool", "default": false }
+            ],
+            "conditions": [{ "path": "Dockerfile", "when": "use_docker" }]
+        }))
+        .write("rust-cli/Cargo.toml", "[package]\nname = \"{{crate_name}}\"\n")
+        .write("rust-cli/src/main.rs", "fn main() { println!(\"{{project_name}}\"); }\n")
+        .write("rust-cli/Dockerfile", "FROM rust\n")
+        .manifest("web/react-app", json!({ "name": "react-app", "language": "TypeScript", "tags": ["web"] }))
+        .write("web/react-app/README.md", "# {{project_name}}\n")
+        .commit("templates")
+}
+
+#[test]
+fn update_list_new_cycle() {
+    let env = Env::new();
+    let commit = standard_source(&env);
+
+    env.update().unwrap();
+
+    let index = env.index();
+    assert_eq!(index.templates.len(), 2);
+    assert!(index.templates.iter().all(|t| t.commit.as_deref() == Some(commit.as_str())));
+    assert!(index.templates.iter().all(|t| t.source_url.as_deref() == Some(env.source.url())));
+
+    assert_eq!(env.list(ListOptions::default()), ["default/rust-cli", "default/web/react-app"]);
+    let rust = ListOptions { language: Some("rust".to_string()), ..Default::default() };
+    assert_eq!(env.list(rust), ["default/rust-cli"]);
+    let web = ListOptions { tags: vec!["web".to_string()], ..Default::default() };
+    assert_eq!(env.list(web), ["default/web/react-app"]);
+
+    let project = env.new_project("rust-cli", "hello-world", &[]).unwrap();
+    assert_eq!(fs::read_to_string(project.join("Cargo.toml")).unwrap(), "[package]\nname = \"hello_world\"\n");
+    assert_eq!(
+        fs::read_to_string(project.join("src/main.rs")).unwrap(),
+        "fn main() { println!(\"hello-world\"); }\n"
+    );
+    assert!(!project.join("Dockerfile").exists());
+    assert!(!project.join("scaffold.json").exists());
+    assert!(project.join(".git").is_dir());
+
+    let project = env.new_project("default/web/react-app", "site", &[]).unwrap();
+    assert_eq!(fs::read_to_string(project.join("README.md")).unwrap(), "# site\n");
+}
+
+#[test]
+fn new_uses_provided_answers() {
+    let env = Env::new();
+    standard_source(&env);
+    env.update().unwrap();
+
+    let project = env.new_project("rust-cli", "svc", &["crate_name=service", "use_docker=true"]).unwrap();
+    assert_eq!(fs::read_to_string(project.join("Cargo.toml")).unwrap(), "[package]\nname = \"service\"\n");
+    assert!(project.join("Dockerfile").is_file());
+
+    // 目标目录已存在时不覆盖
+    assert!(env.new_project("rust-cli", "svc", &[]).is_err());
+}
+
+#[test]
+fn update_fetches_new_commits_and_rollback_restores() {
+    let env = Env::new();
+    let first = standard_source(&env);
+    env.update().unwrap();
+
+    env.source.manifest("go-api", json!({ "name": "go-api", "language": "Go" }));
+    env.source.write("go-api/main.go", "package main\n");
+    let second = env.source.commit("add go-api");
+    env.update().unwrap();
+
+    let index = env.index();
+    assert_eq!(index.templates.len(), 3);
+    assert!(index.templates.iter().all(|t| t.commit.as_deref() == Some(second.as_str())));
+    assert!(env.new_project("go-api", "api", &[]).unwrap().join("main.go").is_file());
+
+    env.rollback().unwrap();
+    let index = env.index();
+    assert_eq!(index.templates.len(), 2);
+    assert!(index.templates.iter().all(|t| t.commit.as_deref() == Some(first.as_str())));
+    assert!(env.new_project("go-api", "api2", &[]).is_err());
+}
+
+#[test]
+fn update_skips_invalid_templates() {
+    let env = Env::new();
+    standard_source(&env);
+    env.source.write("broken/scaffold.json", "{ \"name\": ");
+    env.source.commit("add broken template");
+
+    let error = env.update().unwrap_err();
+    assert_eq!(error.downcast_ref::<InvalidTemplates>().map(|e| e.count), Some(1));
+    assert_eq!(env.list(ListOptions::default()), ["default/rust-cli", "default/web/react-app"]);
+}
+
+#[test]
+fn new_without_index_asks_for_update() {
+    let env = Env::new();
+    assert!(env.list(ListOptions::default()).is_empty());
+    let error = env.new_project("rust-cli", "demo", &[]).unwrap_err();
+    assert!(error.to_string().contains("update"), "{}", error);
+}
+
+#[test]
+fn local_template_repository_is_not_copied() {
+    let env = Env::new();
+    env.source
+        .manifest(".", json!({ "name": "local" }))
+        .write("README.md", "# {{project_name}}\n")
+        .commit("local template");
+    let hash = template_hash(&env.source.work).unwrap();
+
+    let template = env.source.work.to_str().unwrap();
+    let project = env.new_project(template, "fresh", &[]).unwrap();
+    assert_eq!(fs::read_to_string(project.join("README.md")).unwrap(), "# fresh\n");
+
+    // 新项目是重新初始化的空仓库，没有模板的提交
+    assert!(project.join(".git").is_dir());
+    let head = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", "HEAD"])
+        .current_dir(&project)
+        .output()
+        .unwrap();
+    assert!(!head.status.success());
+
+    // 只有提交、内容不变时信任哈希不变
+    git(&env.source.work, &["commit", "--quiet", "--allow-empty", "-m", "empty"]);
+    assert_eq!(template_hash(&env.source.work).unwrap(), hash);
+}
+
+#[test]
+fn into_fails_when_directory_replaces_template_file() {
+    let env = Env::new();
+    standard_source(&env);
+    env.update().unwrap();
+
+    let existing = env.work.join("existing");
+    fs::create_dir_all(existing.join("Cargo.toml")).unwrap();
+    let options = NewOptions {
+        yes: true,
+        no_input: true,
+        into: Some(existing.clone()),
+        conflict: ConflictPolicy::Overwrite,
+        ..Default::default()
+    };
+    let error = NewHandler::new(&env.home, &env.work)
+        .execute(Some("rust-cli".to_string()), None, options)
+        .unwrap_err();
+    assert!(error.to_string().contains("不是文件"), "{}", error);
+    assert!(!existing.join("src").exists());
+}
+
+#[test]
+fn update_with_only_invalid_templates_clears_index() {
+    let env = Env::new();
+    standard_source(&env);
+    env.update().unwrap();
+
+    env.source.write("rust-cli/scaffold.json", "{").write("web/react-app/scaffold.json", "{");
+    let commit = env.source.commit("break all templates");
+    let error = env.update().unwrap_err();
+    assert_eq!(error.downcast_ref::<InvalidTemplates>().map(|e| e.count), Some(2));
+
+    // 索引不能保留旧提交的清单
+    assert!(env.list(ListOptions::default()).is_empty());
+    assert!(env.new_project("rust-cli", "stale", &[]).is_err());
+    assert_eq!(git(&env.home.join("scaffold/codes/default"), &["rev-parse", "HEAD"]), commit);
+}
+
+#[test]
+fn rollback_to_snapshot_without_templates_clears_index() {
+    let env = Env::new();
+    env.source.write("README.md", "templates coming soon\n").commit("empty source");
+    env.update().unwrap();
+    assert!(env.list(ListOptions::default()).is_empty());
+
+    standard_source(&env);
+    env.update().unwrap();
+    assert_eq!(env.list(ListOptions::default()).len(), 2);
+
+    env.rollback().unwrap();
+    assert!(env.list(ListOptions::default()).is_empty());
+    assert!(env.index().templates.is_empty());
+}
+
+#[test]
+fn new_never_generates_fixtures() {
+    let env = Env::new();
+    env.source
+        .manifest(".", json!({ "name": "fixtures" }))
+        .write("README.md", "# {{project_name}}\n")
+        .write("tests/data.json", "{ \"project_name\": \"demo\" }\n")
+        .write("tests/data/README.md", "# demo\n")
+        .write("tests/unit/t.rs", "fn t() {}\n");
+    let template = env.source.work.to_str().unwrap();
+
+    // 用例和快照不会生成到项目中，tests/ 下的其他文件照常生成
+    let project = env.new_project(template, "plain", &[]).unwrap();
+    assert!(!project.join("tests/data.json").exists());
+    assert!(!project.join("tests/data").exists());
+    assert!(project.join("tests/unit/t.rs").is_file());
+
+    // template test 的快照中同样不包含用例本身
+    fs::remove_dir_all(env.source.work.join("tests/data")).unwrap();
+    TemplateHandler::new().test(&env.source.work, true).unwrap();
+    let snapshot = env.source.work.join("tests/data");
+    assert_eq!(fs::read_to_string(snapshot.join("README.md")).unwrap(), "# demo\n");
+    assert!(!snapshot.join("tests/data.json").exists());
+    assert!(snapshot.join("tests/unit/t.rs").is_file());
+    TemplateHandler::new().test(&env.source.work, false).unwrap();
+}
+
+#[test]
+fn pre_generate_hook_sees_final_target_dir() {
+    let env = Env::new();
+    env.source
+        .manifest(".", json!({
+            "name": "hooked",
+            "hooks": { "pre_generate": ["echo \"$SCAFFOLD_TARGET_DIR\" > target.txt", "pwd > pwd.txt"] }
+        }))
+        .write("README.md", "# {{project_name}}\n");
+    let options = NewOptions { yes: true, no_input: true, trust: true, ..Default::default() };
+    NewHandler::new(&env.home, &env.work)
+        .execute(Some(env.source.work.display().to_string()), Some("hooked".to_string()), options)
+        .unwrap();
+
+    let project = env.work.join("hooked");
+    let target = fs::read_to_string(project.join("target.txt")).unwrap();
+    assert_eq!(target.trim(), project.to_str().unwrap());
+    // pre_generate 的工作目录是暂存目录，不是最终目录
+    let pwd = fs::read_to_string(project.join("pwd.txt")).unwrap();
+    assert!(pwd.trim().ends_with(".scaffold-staging"), "{}", pwd);
+}
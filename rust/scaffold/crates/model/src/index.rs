@@ -0,0 +1,344 @@
+//! 本地模板索引
+//!
+//! `~/.scaffold/config.json` 保存模板来源列表和 `update` 解析出的模板。
+//! 最早的版本只保存模板数组，读取时迁移为当前格式
+
+use std::fs;
+use std::path::Path;
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::manifest::TemplateManifest;
+
+/// 当前的索引格式版本
+pub const INDEX_SCHEMA_VERSION: u32 = 1;
+
+/// 默认来源名称
+pub const DEFAULT_SOURCE_NAME: &str = "default";
+
+/// 默认来源的 Git 仓库地址，未配置来源时使用
+pub const DEFAULT_SOURCE_URL: &str = "https://github.com/casemcx/code-template";
+
+/// 模板来源
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Source {
+    /// 来源名称，模板可以通过 `来源/模板` 引用
+    pub name: String,
+    /// Git 仓库地址
+    pub url: String,
+    /// 固定的分支、标签或提交，未设置时使用默认分支的最新提交
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+}
+
+impl Source {
+    /// 由 `url` 或 `url#ref` 创建来源
+    pub fn parse(name: &str, spec: &str) -> Self {
+        let (url, reference) = split_ref(spec);
+        Self {
+            name: name.to_string(),
+            url: url.to_string(),
+            reference: reference.map(String::from),
+        }
+    }
+
+    /// 未配置任何来源时使用的默认来源
+    pub fn default_source() -> Self {
+        Self {
+            name: DEFAULT_SOURCE_NAME.to_string(),
+            url: DEFAULT_SOURCE_URL.to_string(),
+            reference: None,
+        }
+    }
+
+    /// 克隆地址和要检出的 ref
+    ///
+    /// 手动写入配置文件的 `url#ref` 同样生效，`ref` 字段优先
+    pub fn remote(&self) -> (&str, Option<&str>) {
+        let (url, reference) = split_ref(&self.url);
+        (url, self.reference.as_deref().or(reference))
+    }
+}
+
+/// 拆分 `url#ref`，`#` 后为空时视为未指定
+fn split_ref(spec: &str) -> (&str, Option<&str>) {
+    match spec.rsplit_once('#') {
+        Some((url, reference)) if !reference.is_empty() => (url, Some(reference)),
+        Some((url, _)) => (url, None),
+        None => (spec, None),
+    }
+}
+
+/// 检查来源名称：只能包含字母、数字、`-`、`_`、`.`，且不能以 `.` 开头
+pub fn check_source_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        anyhow::bail!("来源名称 {} 无效：只能包含字母、数字、-、_、.，且不能以 . 开头", name);
+    }
+    Ok(())
+}
+
+/// 展示用的短提交 SHA
+pub fn short_commit(commit: &str) -> &str {
+    &commit[..commit.len().min(8)]
+}
+
+/// 索引中的一个模板：模板清单加上 `update` 记录的位置信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateEntry {
+    #[serde(flatten)]
+    pub manifest: TemplateManifest,
+    /// 模板在来源仓库中的分类，如 `frontend`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// 模板来源名称，本地目录模板为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// 模板来源仓库地址，用于钩子信任记录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+    /// update 时模板来源检出的提交
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// 模板目录
+    #[serde(default)]
+    pub path: String,
+}
+
+impl TemplateEntry {
+    /// 本地目录中的模板，没有来源信息
+    pub fn local(manifest: TemplateManifest, path: &Path) -> Self {
+        Self {
+            manifest,
+            category: None,
+            source: None,
+            source_url: None,
+            commit: None,
+            path: path.to_string_lossy().to_string(),
+        }
+    }
+
+    /// 模板名称
+    pub fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    /// 带来源前缀的模板名称，如 `default/nextjs`
+    pub fn display_name(&self) -> String {
+        match &self.source {
+            Some(source) => format!("{}/{}", source, self.manifest.name),
+            None => self.manifest.name.clone(),
+        }
+    }
+
+    /// 带来源和分类前缀的完整模板名称，如 `default/frontend/nextjs`
+    pub fn qualified_name(&self) -> String {
+        self.source
+            .iter()
+            .chain(&self.category)
+            .chain(std::iter::once(&self.manifest.name))
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// 名称是否指向该模板：模板名称，或以 `来源/`、`分类/`、`来源/分类/` 为前缀的模板名称
+    pub fn matches_name(&self, name: &str) -> bool {
+        let Some(prefix) = name.strip_suffix(self.manifest.name.as_str()) else {
+            return false;
+        };
+        let Some(prefix) = prefix.strip_suffix('/') else {
+            return prefix.is_empty();
+        };
+
+        let source = self.source.as_deref();
+        let category = self.category.as_deref();
+        Some(prefix) == source
+            || Some(prefix) == category
+            || matches!((source, category), (Some(s), Some(c)) if prefix == format!("{}/{}", s, c))
+    }
+}
+
+/// 本地模板索引，对应 `~/.scaffold/config.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateIndex {
+    /// 格式版本
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    /// 模板来源，未配置时使用默认来源
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sources: Option<Vec<Source>>,
+    /// update 解析出的模板
+    #[serde(default)]
+    pub templates: Vec<TemplateEntry>,
+}
+
+fn default_schema_version() -> u32 {
+    INDEX_SCHEMA_VERSION
+}
+
+impl Default for TemplateIndex {
+    fn default() -> Self {
+        Self {
+            schema_version: INDEX_SCHEMA_VERSION,
+            sources: None,
+            templates: Vec::new(),
+        }
+    }
+}
+
+impl TemplateIndex {
+    /// 读取索引，文件不存在时返回空索引
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("读取配置文件 {} 失败", path.display()))?;
+        let value: Value = serde_json::from_str(&content)
+            .with_context(|| format!("解析配置文件 {} 失败", path.display()))?;
+        Self::migrate(value).with_context(|| format!("解析配置文件 {} 失败", path.display()))
+    }
+
+    /// 将任意版本的索引内容转换为当前格式
+    ///
+    /// 模板数组是最早的格式，视为没有配置来源；没有 `schema_version` 的对象视为版本 1
+    pub fn migrate(value: Value) -> Result<Self> {
+        let index = match value {
+            Value::Array(_) => Self {
+                templates: serde_json::from_value(value)?,
+                ..Self::default()
+            },
+            _ => serde_json::from_value::<Self>(value)?,
+        };
+
+        if index.schema_version > INDEX_SCHEMA_VERSION {
+            anyhow::bail!(
+                "索引的 schema_version 为 {}，当前最高支持 {}，请升级 scaffold",
+                index.schema_version,
+                INDEX_SCHEMA_VERSION
+            );
+        }
+        Ok(Self { schema_version: INDEX_SCHEMA_VERSION, ..index })
+    }
+
+    /// 写入索引
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("创建目录 {} 失败", parent.display()))?;
+        }
+
+        let json_content = serde_json::to_string_pretty(self)
+            .with_context(|| "序列化配置失败")?;
+        fs::write(path, json_content)
+            .with_context(|| format!("写入配置文件 {} 失败", path.display()))?;
+        Ok(())
+    }
+
+    /// 实际生效的来源列表
+    pub fn sources(&self) -> Vec<Source> {
+        self.sources.clone().unwrap_or_else(|| vec![Source::default_source()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(name: &str, source: &str, category: Option<&str>) -> TemplateEntry {
+        TemplateEntry {
+            category: category.map(String::from),
+            source: Some(source.to_string()),
+            source_url: Some("https://example.com/templates.git".to_string()),
+            commit: Some("0123456789abcdef".to_string()),
+            path: format!("/codes/{}/{}", source, name),
+            ..TemplateEntry::local(TemplateManifest::new(name), Path::new(""))
+        }
+    }
+
+    #[test]
+    fn index_round_trip() {
+        let index = TemplateIndex {
+            schema_version: INDEX_SCHEMA_VERSION,
+            sources: Some(vec![Source::parse("team", "https://example.com/templates.git#v1")]),
+            templates: vec![entry("react-app", "team", Some("frontend")), entry("cli", "team", None)],
+        };
+
+        let value = serde_json::to_value(&index).unwrap();
+        assert_eq!(value["sources"][0]["ref"], "v1");
+        assert_eq!(value["templates"][0]["name"], "react-app");
+        assert_eq!(value["templates"][0]["category"], "frontend");
+        assert!(value["templates"][1].get("category").is_none());
+        assert_eq!(TemplateIndex::migrate(value).unwrap(), index);
+    }
+
+    #[test]
+    fn migrate_bare_array() {
+        let legacy = json!([
+            {
+                "name": "nextjs",
+                "description": "Next.js",
+                "version": null,
+                "author": null,
+                "language": "TypeScript",
+                "tags": ["web"],
+                "path": "/home/u/scaffold/codes/nextjs"
+            }
+        ]);
+
+        let index = TemplateIndex::migrate(legacy).unwrap();
+        assert_eq!(index.schema_version, INDEX_SCHEMA_VERSION);
+        assert_eq!(index.sources, None);
+        assert_eq!(index.sources(), vec![Source::default_source()]);
+        assert_eq!(index.templates.len(), 1);
+        assert_eq!(index.templates[0].name(), "nextjs");
+        assert_eq!(index.templates[0].manifest.language.as_deref(), Some("TypeScript"));
+        assert_eq!(index.templates[0].path, "/home/u/scaffold/codes/nextjs");
+        assert_eq!(index.templates[0].source, None);
+    }
+
+    #[test]
+    fn migrate_object_without_version() {
+        let value = json!({
+            "sources": [{ "name": "default", "url": DEFAULT_SOURCE_URL }],
+            "templates": [{ "name": "cli", "source": "default", "path": "/codes/default/cli" }]
+        });
+
+        let index = TemplateIndex::migrate(value).unwrap();
+        assert_eq!(index.schema_version, INDEX_SCHEMA_VERSION);
+        assert_eq!(index.templates[0].display_name(), "default/cli");
+    }
+
+    #[test]
+    fn newer_index_is_rejected() {
+        let value = json!({ "schema_version": INDEX_SCHEMA_VERSION + 1, "templates": [] });
+        assert!(TemplateIndex::migrate(value).is_err());
+    }
+
+    #[test]
+    fn source_ref_syntax() {
+        let source = Source::parse("team", "https://example.com/t.git#main");
+        assert_eq!(source.url, "https://example.com/t.git");
+        assert_eq!(source.remote(), ("https://example.com/t.git", Some("main")));
+
+        let source = Source { reference: Some("v2".to_string()), ..Source::parse("team", "https://example.com/t.git#main") };
+        assert_eq!(source.remote().1, Some("v2"));
+    }
+
+    #[test]
+    fn template_name_matching() {
+        let template = entry("app", "team", Some("frontend"));
+        assert_eq!(template.qualified_name(), "team/frontend/app");
+        for name in ["app", "team/app", "frontend/app", "team/frontend/app"] {
+            assert!(template.matches_name(name), "{}", name);
+        }
+        for name in ["ap", "backend/app", "xapp", "team/backend/app"] {
+            assert!(!template.matches_name(name), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! 模板数据模型
+//!
+//! 模板目录中的 `scaffold.json`（[`TemplateManifest`]）和本地模板索引
+//! `~/.scaffold/config.json`（[`TemplateIndex`]）的唯一定义，`update` 写入和 `new` 读取共用
+
+pub mod index;
+pub mod manifest;
+pub mod variables;
+
+pub use index::{Source, TemplateEntry, TemplateIndex};
+pub use manifest::{manifest_schema, ConditionRule, Hooks, TemplateManifest};
+pub use variables::{TemplateVariable, VariableKind};
@@ -0,0 +1,177 @@
+//! 模板清单
+//!
+//! 每个模板目录下的 `scaffold.json`，声明模板的元数据、变量、条件文件和钩子
+
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::variables::TemplateVariable;
+
+/// 当前支持的 scaffold.json 格式版本
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// 模板清单，对应模板目录下的 `scaffold.json`
+///
+/// JSON Schema 不允许未知字段，用于发现拼错的键；反序列化仍然忽略未知字段，
+/// 因为模板索引中的条目会把清单和索引字段展开在同一层
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct TemplateManifest {
+    /// 格式版本，缺省为 1
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// 生成前询问的变量
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<TemplateVariable>,
+    /// 条件文件规则
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<ConditionRule>,
+    /// 生成前后执行的命令
+    #[serde(default, skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
+}
+
+fn default_schema_version() -> u32 {
+    MANIFEST_SCHEMA_VERSION
+}
+
+impl TemplateManifest {
+    /// 只包含名称的清单
+    pub fn new(name: &str) -> Self {
+        Self {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            name: name.to_string(),
+            description: None,
+            version: None,
+            author: None,
+            language: None,
+            tags: None,
+            variables: Vec::new(),
+            conditions: Vec::new(),
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// 检查格式版本是否受支持
+    pub fn check_version(&self) -> Result<()> {
+        if self.schema_version > MANIFEST_SCHEMA_VERSION {
+            anyhow::bail!(
+                "模板 {} 的 schema_version 为 {}，当前最高支持 {}，请升级 scaffold",
+                self.name,
+                self.schema_version,
+                MANIFEST_SCHEMA_VERSION
+            );
+        }
+        Ok(())
+    }
+}
+
+/// 由清单类型生成的 scaffold.json JSON Schema
+pub fn manifest_schema() -> Value {
+    schemars::schema_for!(TemplateManifest).to_value()
+}
+
+/// 条件规则
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ConditionRule {
+    /// 相对模板根目录的 glob，如 `docker/**`
+    pub path: String,
+    /// Handlebars 表达式，如 `use_docker`、`(eq license "MIT")`、`(contains features "ci")`
+    pub when: String,
+}
+
+/// 模板钩子
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Hooks {
+    /// 写入文件之前执行
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_generate: Vec<String>,
+    /// 项目生成、Git 初始化完成之后执行
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_generate: Vec<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre_generate.is_empty() && self.post_generate.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variables::VariableKind;
+    use serde_json::json;
+
+    #[test]
+    fn minimal_manifest_defaults() {
+        let manifest: TemplateManifest = serde_json::from_value(json!({ "name": "demo" })).unwrap();
+        assert_eq!(manifest, TemplateManifest::new("demo"));
+        assert!(manifest.check_version().is_ok());
+    }
+
+    #[test]
+    fn full_manifest_round_trip() {
+        let value = json!({
+            "schema_version": 1,
+            "name": "axum-api",
+            "description": "Axum 服务",
+            "version": "1.0.0",
+            "author": "team",
+            "language": "Rust",
+            "tags": ["web", "api"],
+            "variables": [
+                { "name": "port", "type": "number", "default": 8080, "help": "监听端口" },
+                { "name": "db", "type": "select", "options": ["postgres", "sqlite"], "default": "sqlite" },
+                { "name": "features", "type": "multiselect", "options": ["a", "b"], "default": ["a"] },
+                { "name": "docker", "type": "bool", "default": true, "prompt": "使用 Docker?" },
+                { "name": "crate_name", "type": "string", "default": "{{project_name}}", "validate": "^[a-z_]+$" }
+            ],
+            "conditions": [{ "path": "docker/**", "when": "docker" }],
+            "hooks": { "pre_generate": ["echo pre"], "post_generate": ["cargo fmt"] }
+        });
+
+        let manifest: TemplateManifest = serde_json::from_value(value.clone()).unwrap();
+        assert!(matches!(manifest.variables[1].kind, VariableKind::Select { .. }));
+        assert_eq!(manifest.hooks.post_generate, ["cargo fmt"]);
+        assert_eq!(serde_json::to_value(&manifest).unwrap(), value);
+    }
+
+    #[test]
+    fn schema_describes_manifest() {
+        let schema = manifest_schema();
+        assert_eq!(schema["required"], json!(["name"]));
+        for field in ["schema_version", "variables", "conditions", "hooks"] {
+            assert!(schema["properties"].get(field).is_some(), "{}", field);
+        }
+        assert_eq!(schema["additionalProperties"], json!(false));
+    }
+
+    #[test]
+    fn unknown_fields_are_ignored_when_deserializing() {
+        let manifest: TemplateManifest =
+            serde_json::from_value(json!({ "name": "demo", "category": "web" })).unwrap();
+        assert_eq!(manifest, TemplateManifest::new("demo"));
+    }
+
+    #[test]
+    fn newer_schema_version_is_rejected() {
+        let manifest: TemplateManifest =
+            serde_json::from_value(json!({ "schema_version": MANIFEST_SCHEMA_VERSION + 1, "name": "demo" })).unwrap();
+        assert!(manifest.check_version().is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! 模板变量定义
+//!
+//! 模板在 scaffold.json 的 `variables` 中声明变量，`new` 命令按顺序询问后写入渲染上下文
+
+use anyhow::{Result, Context};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+
+/// 渲染上下文中的保留名称，变量不能使用
+pub const RESERVED_NAMES: &[&str] = &["project_name", "template"];
+
+/// 钩子环境变量中的保留名称，变量导出为 `SCAFFOLD_<大写变量名>` 后会与其冲突
+pub const RESERVED_ENV_NAMES: &[&str] = &["target_dir"];
+
+/// 模板变量
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TemplateVariable {
+    /// 变量名，在模板中通过 `{{name}}` 引用
+    pub name: String,
+    /// 变量类型及默认值
+    #[serde(flatten)]
+    pub kind: VariableKind,
+    /// 提示文字，缺省时使用变量名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    /// 帮助说明
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    /// 校验输入的正则表达式，仅对 string 和 number 生效
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validate: Option<String>,
+}
+
+/// 变量类型
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum VariableKind {
+    /// 字符串，默认值可以引用前面的变量，如 `{{project_name}}`
+    String {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<String>,
+    },
+    /// 布尔值
+    Bool {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<bool>,
+    },
+    /// 数字
+    Number {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<Number>,
+    },
+    /// 单选
+    Select {
+        options: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<String>,
+    },
+    /// 多选
+    Multiselect {
+        options: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<Vec<String>>,
+    },
+}
+
+impl VariableKind {
+    /// 类型名称，与 scaffold.json 中的 `type` 一致
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            VariableKind::String { .. } => "string",
+            VariableKind::Bool { .. } => "bool",
+            VariableKind::Number { .. } => "number",
+            VariableKind::Select { .. } => "select",
+            VariableKind::Multiselect { .. } => "multiselect",
+        }
+    }
+
+    /// scaffold.json 中声明的默认值，字符串默认值未经渲染
+    pub fn declared_default(&self) -> Option<Value> {
+        match self {
+            VariableKind::String { default } => default.clone().map(Value::String),
+            VariableKind::Bool { default } => default.map(Value::Bool),
+            VariableKind::Number { default } => default.clone().map(Value::Number),
+            VariableKind::Select { default, .. } => default.clone().map(Value::String),
+            VariableKind::Multiselect { default, .. } => default
+                .as_ref()
+                .map(|d| Value::Array(d.iter().cloned().map(Value::String).collect())),
+        }
+    }
+
+    /// select 和 multiselect 的可选项
+    pub fn options(&self) -> Option<&[String]> {
+        match self {
+            VariableKind::Select { options, .. } | VariableKind::Multiselect { options, .. } => Some(options),
+            _ => None,
+        }
+    }
+}
+
+impl TemplateVariable {
+    /// 提示文字
+    pub fn prompt_text(&self) -> &str {
+        self.prompt.as_deref().unwrap_or(&self.name)
+    }
+
+    /// 编译校验正则
+    pub fn validator(&self) -> Result<Option<Regex>> {
+        self.validate
+            .as_deref()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .with_context(|| format!("变量 {} 的校验正则 {} 无效", self.name, pattern))
+            })
+            .transpose()
+    }
+
+    /// 检查变量定义本身是否合法
+    pub fn check(&self) -> Result<()> {
+        if self.name.is_empty() {
+            anyhow::bail!("变量名不能为空");
+        }
+        if RESERVED_NAMES.contains(&self.name.as_str()) {
+            anyhow::bail!("变量名 {} 是保留名称", self.name);
+        }
+        if RESERVED_ENV_NAMES.iter().any(|n| n.eq_ignore_ascii_case(&self.name)) {
+            anyhow::bail!("变量名 {} 是保留名称，会与钩子环境变量 SCAFFOLD_{} 冲突", self.name, self.name.to_ascii_uppercase());
+        }
+        self.validator()?;
+
+        match &self.kind {
+            VariableKind::Select { options, default } => {
+                if options.is_empty() {
+                    anyhow::bail!("变量 {} 的 options 不能为空", self.name);
+                }
+                if let Some(default) = default {
+                    if !options.contains(default) {
+                        anyhow::bail!("变量 {} 的默认值 {} 不在 options 中", self.name, default);
+                    }
+                }
+            }
+            VariableKind::Multiselect { options, default } => {
+                if options.is_empty() {
+                    anyhow::bail!("变量 {} 的 options 不能为空", self.name);
+                }
+                for value in default.iter().flatten() {
+                    if !options.contains(value) {
+                        anyhow::bail!("变量 {} 的默认值 {} 不在 options 中", self.name, value);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// 将文本输入解析为变量值并校验
+    pub fn parse_value(&self, input: &str) -> Result<Value> {
+        if let Some(regex) = self.validator()? {
+            if matches!(self.kind, VariableKind::String { .. } | VariableKind::Number { .. })
+                && !regex.is_match(input)
+            {
+                anyhow::bail!("变量 {} 的值 {} 不匹配 {}", self.name, input, regex.as_str());
+            }
+        }
+
+        match &self.kind {
+            VariableKind::String { .. } => Ok(Value::String(input.to_string())),
+            VariableKind::Bool { .. } => match input.trim().to_lowercase().as_str() {
+                "true" | "yes" | "y" | "1" => Ok(Value::Bool(true)),
+                "false" | "no" | "n" | "0" => Ok(Value::Bool(false)),
+                _ => anyhow::bail!("变量 {} 需要布尔值，得到 {}", self.name, input),
+            },
+            VariableKind::Number { .. } => parse_number(input.trim())
+                .map(Value::Number)
+                .ok_or_else(|| anyhow::anyhow!("变量 {} 需要数字，得到 {}", self.name, input)),
+            VariableKind::Select { options, .. } => {
+                if !options.iter().any(|o| o == input) {
+                    anyhow::bail!("变量 {} 的值 {} 不在可选项 {:?} 中", self.name, input, options);
+                }
+                Ok(Value::String(input.to_string()))
+            }
+            VariableKind::Multiselect { options, .. } => {
+                let values: Vec<Value> = input
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(|v| {
+                        if options.iter().any(|o| o == v) {
+                            Ok(Value::String(v.to_string()))
+                        } else {
+                            anyhow::bail!("变量 {} 的值 {} 不在可选项 {:?} 中", self.name, v, options)
+                        }
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Value::Array(values))
+            }
+        }
+    }
+}
+
+/// 解析数字，整数优先以保持渲染结果不带小数点
+pub fn parse_number(input: &str) -> Option<Number> {
+    if let Ok(value) = input.parse::<i64>() {
+        return Some(Number::from(value));
+    }
+    input.parse::<f64>().ok().and_then(Number::from_f64)
+}
+
+/// 检查一组变量定义
+pub fn check_variables(variables: &[TemplateVariable]) -> Result<()> {
+    for (index, variable) in variables.iter().enumerate() {
+        variable.check()?;
+        if variables[..index].iter().any(|v| v.name == variable.name) {
+            anyhow::bail!("变量 {} 重复定义", variable.name);
+        }
+    }
+    Ok(())
+}
@@ -18,7 +18,7 @@ impl GitRepo {
             .arg(&self.url)
             .arg(target_dir)
             .output()
-            .with_context(|| format!("Failed to execute git clone command"))?;
+            .with_context(|| "Failed to execute git clone command")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -27,4 +27,116 @@ impl GitRepo {
 
         Ok(())
     }
+
+    /// 克隆到 `target_dir`，指定了 `reference` 时检出该 ref
+    ///
+    /// 返回工作区所在的提交 SHA
+    pub fn clone_at(&self, target_dir: &str, reference: Option<&str>) -> Result<String> {
+        self.clone_to(target_dir)?;
+        match reference {
+            Some(reference) => Self::checkout(target_dir, reference),
+            None => Self::head_commit(target_dir),
+        }
+    }
+
+    /// 以本地克隆的方式复制已有克隆 `repo_dir` 到 `target_dir`，不检出工作区
+    ///
+    /// 对象通过硬链接共享，不复制仓库内容。`origin` 指回本实例的地址，之后可以直接调用 [`GitRepo::sync`]
+    pub fn clone_local(&self, repo_dir: &str, target_dir: &str) -> Result<()> {
+        let output = Command::new("git")
+            .args(["clone", "--quiet", "--local", "--no-checkout"])
+            .arg(repo_dir)
+            .arg(target_dir)
+            .output()
+            .with_context(|| "Failed to execute git clone command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Git clone failed: {}", stderr);
+        }
+
+        // 本地克隆的 origin 是 repo_dir，需要改回真正的来源地址
+        run(target_dir, &["remote", "set-url", "origin", &self.url])?;
+        Ok(())
+    }
+
+    /// 在已有克隆中以分离 HEAD 的方式检出分支、标签或提交
+    ///
+    /// 分支名优先按 `origin` 解析，没有对应本地分支的远程分支同样可用。返回解析后的提交 SHA
+    pub fn checkout(repo_dir: &str, reference: &str) -> Result<String> {
+        let commit = Self::resolve_ref(repo_dir, reference)?;
+
+        run(repo_dir, &["checkout", "--quiet", "--detach", &commit])
+            .with_context(|| format!("Failed to check out '{}'", reference))?;
+
+        Ok(commit)
+    }
+
+    /// 将已有克隆更新到 `origin` 的最新状态
+    ///
+    /// 拉取分支和标签后，把工作区强制重置到 `reference`，未指定时重置到远程默认分支。
+    /// 返回工作区所在的提交 SHA
+    pub fn sync(repo_dir: &str, reference: Option<&str>) -> Result<String> {
+        run(repo_dir, &["fetch", "--quiet", "--prune", "--tags", "--force", "origin"])?;
+
+        let commit = match reference {
+            Some(reference) => Self::resolve_ref(repo_dir, reference)?,
+            None => {
+                // 克隆之后远程默认分支可能已经改变
+                run(repo_dir, &["remote", "set-head", "origin", "--auto"])?;
+                Self::resolve(repo_dir, "origin/HEAD")?
+            }
+        };
+
+        run(repo_dir, &["reset", "--quiet", "--hard", &commit])?;
+        run(repo_dir, &["clean", "--quiet", "-ffdx"])?;
+
+        Ok(commit)
+    }
+
+    /// 已有克隆中 `origin` 远程的地址
+    pub fn remote_url(repo_dir: &str) -> Result<String> {
+        run(repo_dir, &["remote", "get-url", "origin"])
+    }
+
+    /// 当前 HEAD 的提交 SHA
+    pub fn head_commit(repo_dir: &str) -> Result<String> {
+        Self::resolve(repo_dir, "HEAD")
+    }
+
+    /// 解析分支、标签或提交，优先使用远程分支
+    fn resolve_ref(repo_dir: &str, reference: &str) -> Result<String> {
+        Self::resolve(repo_dir, &format!("origin/{}", reference))
+            .or_else(|_| Self::resolve(repo_dir, reference))
+            .with_context(|| format!("Git ref '{}' not found", reference))
+    }
+
+    /// 将版本解析为完整的提交 SHA
+    pub fn resolve(repo_dir: &str, rev: &str) -> Result<String> {
+        run(
+            repo_dir,
+            &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", rev)],
+        )
+    }
+}
+
+/// 在 `repo_dir` 中执行 git 命令，返回去掉首尾空白的标准输出
+fn run(repo_dir: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to execute git {}", args[0]))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = stderr.trim();
+        if stderr.is_empty() {
+            anyhow::bail!("Git {} failed", args[0]);
+        }
+        anyhow::bail!("Git {} failed: {}", args[0], stderr);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
@@ -1,6 +1,7 @@
 pub mod copy;
 pub mod git;
 pub mod logger;
+pub mod size;
 
 // 重新导出日志宏，方便使用
 pub use logger::{info, debug, warn, error, trace};
\ No newline at end of file
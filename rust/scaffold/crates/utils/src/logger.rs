@@ -5,11 +5,12 @@
 //! # 示例
 //!
 //! ```no_run
-//! use utils::log;
+//! use utils::logger;
 //!
-//! log::info!("应用启动");
-//! log::warn!("配置文件不存在");
-//! log::error!("连接失败: {}", err);
+//! # let err = "timeout";
+//! logger::info!("应用启动");
+//! logger::warn!("配置文件不存在");
+//! logger::error!("连接失败: {}", err);
 //! ```
 
 /// 重新导出 tracing 的日志宏
@@ -0,0 +1,25 @@
+/// 格式化文件大小
+///
+/// # 示例
+///
+/// ```
+/// use utils::size::format_size;
+///
+/// assert_eq!(format_size(512), "512 B");
+/// assert_eq!(format_size(1536), "1.5 KB");
+/// ```
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}